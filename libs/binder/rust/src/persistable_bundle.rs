@@ -22,10 +22,17 @@ use crate::{
 };
 use binder_ndk_sys::{
     APersistableBundle, APersistableBundle_delete, APersistableBundle_dup,
-    APersistableBundle_erase, APersistableBundle_getBoolean, APersistableBundle_getBooleanVector,
-    APersistableBundle_getDouble, APersistableBundle_getDoubleVector, APersistableBundle_getInt,
-    APersistableBundle_getIntVector, APersistableBundle_getLong, APersistableBundle_getLongVector,
-    APersistableBundle_getPersistableBundle, APersistableBundle_isEqual, APersistableBundle_new,
+    APersistableBundle_erase, APersistableBundle_getBoolean, APersistableBundle_getBooleanKeys,
+    APersistableBundle_getBooleanVector, APersistableBundle_getBooleanVectorKeys,
+    APersistableBundle_getDouble, APersistableBundle_getDoubleKeys,
+    APersistableBundle_getDoubleVector, APersistableBundle_getDoubleVectorKeys,
+    APersistableBundle_getInt, APersistableBundle_getIntKeys, APersistableBundle_getIntVector,
+    APersistableBundle_getIntVectorKeys, APersistableBundle_getLong,
+    APersistableBundle_getLongKeys, APersistableBundle_getLongVector,
+    APersistableBundle_getLongVectorKeys, APersistableBundle_getPersistableBundle,
+    APersistableBundle_getPersistableBundleKeys, APersistableBundle_getString,
+    APersistableBundle_getStringKeys, APersistableBundle_getStringVector,
+    APersistableBundle_getStringVectorKeys, APersistableBundle_new,
     APersistableBundle_putBoolean, APersistableBundle_putBooleanVector,
     APersistableBundle_putDouble, APersistableBundle_putDoubleVector, APersistableBundle_putInt,
     APersistableBundle_putIntVector, APersistableBundle_putLong, APersistableBundle_putLongVector,
@@ -33,19 +40,272 @@ use binder_ndk_sys::{
     APersistableBundle_putStringVector, APersistableBundle_readFromParcel, APersistableBundle_size,
     APersistableBundle_writeToParcel, APERSISTABLEBUNDLE_KEY_NOT_FOUND,
 };
-use std::ffi::{c_char, CString, NulError};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{c_char, c_void, CString, NulError};
 use std::ptr::{null_mut, NonNull};
 
+/// An error which occurred while getting a vector value from a `PersistableBundle`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum GetVecError {
+    /// The given key contained a NUL character.
+    InvalidKey(NulError),
+    /// Allocating a buffer to hold the result failed.
+    Alloc(StatusCode),
+}
+
+impl From<NulError> for GetVecError {
+    fn from(e: NulError) -> Self {
+        Self::InvalidKey(e)
+    }
+}
+
+/// The type of value held by a key in a `PersistableBundle`, as reported by `value_type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ValueType {
+    Bool,
+    Int,
+    Long,
+    Double,
+    String,
+    BoolVec,
+    IntVec,
+    LongVec,
+    DoubleVec,
+    StringVec,
+    Bundle,
+}
+
+/// Conflict-resolution policy used by `PersistableBundle::merge_from` and
+/// `PersistableBundle::overlay` when both bundles hold a scalar or vector value under the same
+/// key. Nested bundles are always merged recursively, regardless of this policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// The value from the other bundle replaces the value already present.
+    Overwrite,
+    /// The value already present is kept, and the other bundle's value is discarded.
+    Keep,
+}
+
+/// A type-erased value that can be stored in a `PersistableBundle`, as used by `insert`, `get` and
+/// `entry`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i32),
+    Long(i64),
+    Double(f64),
+    String(String),
+    BoolVec(Vec<bool>),
+    IntVec(Vec<i32>),
+    LongVec(Vec<i64>),
+    DoubleVec(Vec<f64>),
+    StringVec(Vec<String>),
+    Bundle(PersistableBundle),
+}
+
+impl Value {
+    /// Returns the `ValueType` of this value.
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Self::Bool(_) => ValueType::Bool,
+            Self::Int(_) => ValueType::Int,
+            Self::Long(_) => ValueType::Long,
+            Self::Double(_) => ValueType::Double,
+            Self::String(_) => ValueType::String,
+            Self::BoolVec(_) => ValueType::BoolVec,
+            Self::IntVec(_) => ValueType::IntVec,
+            Self::LongVec(_) => ValueType::LongVec,
+            Self::DoubleVec(_) => ValueType::DoubleVec,
+            Self::StringVec(_) => ValueType::StringVec,
+            Self::Bundle(_) => ValueType::Bundle,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::Long(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::Double(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Vec<bool>> for Value {
+    fn from(value: Vec<bool>) -> Self {
+        Self::BoolVec(value)
+    }
+}
+
+impl From<Vec<i32>> for Value {
+    fn from(value: Vec<i32>) -> Self {
+        Self::IntVec(value)
+    }
+}
+
+impl From<Vec<i64>> for Value {
+    fn from(value: Vec<i64>) -> Self {
+        Self::LongVec(value)
+    }
+}
+
+impl From<Vec<f64>> for Value {
+    fn from(value: Vec<f64>) -> Self {
+        Self::DoubleVec(value)
+    }
+}
+
+impl From<Vec<String>> for Value {
+    fn from(value: Vec<String>) -> Self {
+        Self::StringVec(value)
+    }
+}
+
+impl From<PersistableBundle> for Value {
+    fn from(value: PersistableBundle) -> Self {
+        Self::Bundle(value)
+    }
+}
+
+/// The `APersistableBundle_stringAllocator` callback used by `get_string`, `get_string_vec` and
+/// `keys`: appends a new zero-filled buffer of the requested size to the `Vec<Vec<u8>>` pointed to
+/// by `context`, for the NDK to write a NUL-terminated string into, and returns a pointer to it.
+extern "C" fn string_allocator(size: i32, context: *mut c_void) -> *mut c_char {
+    // SAFETY: Every caller below passes a valid, live `*mut Vec<Vec<u8>>` as `context` for the
+    // duration of the enclosing FFI call, and doesn't otherwise touch it until the call returns.
+    let buffers = unsafe { &mut *context.cast::<Vec<Vec<u8>>>() };
+    let mut buffer = vec![0u8; size.max(0) as usize];
+    let ptr = buffer.as_mut_ptr().cast::<c_char>();
+    buffers.push(buffer);
+    ptr
+}
+
+/// Converts a buffer produced by `string_allocator`, which the NDK has written a NUL-terminated
+/// string into, to an owned `String`.
+fn buffer_into_string(buffer: Vec<u8>) -> String {
+    let len = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..len]).into_owned()
+}
+
+/// Appends `data`, prefixed by its length as a little-endian `u32`, to `bytes`.
+fn encode_len_prefixed(bytes: &mut Vec<u8>, data: &[u8]) {
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(data);
+}
+
+/// Appends the canonical encoding of a single `Value` to `bytes`: a one-byte type tag (matching
+/// the order of `ValueType`'s variants) followed by a type-specific payload.
+fn encode_value(bytes: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Bool(value) => {
+            bytes.push(0);
+            bytes.push(u8::from(*value));
+        }
+        Value::Int(value) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::Long(value) => {
+            bytes.push(2);
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::Double(value) => {
+            bytes.push(3);
+            bytes.extend_from_slice(&value.to_bits().to_le_bytes());
+        }
+        Value::String(value) => {
+            bytes.push(4);
+            encode_len_prefixed(bytes, value.as_bytes());
+        }
+        Value::BoolVec(value) => {
+            bytes.push(5);
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            bytes.extend(value.iter().map(|element| u8::from(*element)));
+        }
+        Value::IntVec(value) => {
+            bytes.push(6);
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            for element in value {
+                bytes.extend_from_slice(&element.to_le_bytes());
+            }
+        }
+        Value::LongVec(value) => {
+            bytes.push(7);
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            for element in value {
+                bytes.extend_from_slice(&element.to_le_bytes());
+            }
+        }
+        Value::DoubleVec(value) => {
+            bytes.push(8);
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            for element in value {
+                bytes.extend_from_slice(&element.to_bits().to_le_bytes());
+            }
+        }
+        Value::StringVec(value) => {
+            bytes.push(9);
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            for element in value {
+                encode_len_prefixed(bytes, element.as_bytes());
+            }
+        }
+        Value::Bundle(value) => {
+            bytes.push(10);
+            encode_len_prefixed(bytes, &value.to_canonical_bytes());
+        }
+    }
+}
+
 /// A mapping from string keys to values of various types.
 #[derive(Debug)]
 pub struct PersistableBundle(NonNull<APersistableBundle>);
 
 impl PersistableBundle {
-    /// Creates a new `PersistableBundle`.
-    pub fn new() -> Self {
+    /// Creates a new `PersistableBundle`, returning an error if allocation fails.
+    pub fn try_new() -> Result<Self, StatusCode> {
         // SAFETY: APersistableBundle_new doesn't actually have any safety requirements.
         let bundle = unsafe { APersistableBundle_new() };
-        Self(NonNull::new(bundle).expect("Allocated APersistableBundle was null"))
+        NonNull::new(bundle).map(Self).ok_or(StatusCode::NO_MEMORY)
+    }
+
+    /// Creates a new `PersistableBundle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if allocation fails. Use `try_new` to handle that case instead.
+    pub fn new() -> Self {
+        Self::try_new().expect("Allocated APersistableBundle was null")
+    }
+
+    /// Duplicates the bundle, returning an error if allocation fails.
+    pub fn try_clone(&self) -> Result<Self, StatusCode> {
+        // SAFETY: The wrapped `APersistableBundle` pointer is guaranteed to be valid for the
+        // lifetime of the `PersistableBundle`.
+        let duplicate = unsafe { APersistableBundle_dup(self.0.as_ptr()) };
+        NonNull::new(duplicate).map(Self).ok_or(StatusCode::NO_MEMORY)
     }
 
     /// Returns the number of mappings in the bundle.
@@ -374,10 +634,40 @@ impl PersistableBundle {
         }
     }
 
-    /// Gets the vector of `T` associated with the given key.
+    /// Gets the string value associated with the given key.
     ///
     /// Returns an error if the key contains a NUL character, or `Ok(None)` if the key doesn't exist
     /// in the bundle.
+    pub fn get_string(&self, key: &str) -> Result<Option<String>, NulError> {
+        let key = CString::new(key)?;
+        let mut out_value: *mut c_char = null_mut();
+        let mut buffers: Vec<Vec<u8>> = Vec::new();
+        let context = (&mut buffers as *mut Vec<Vec<u8>>).cast::<c_void>();
+        // SAFETY: The wrapped `APersistableBundle` pointer is guaranteed to be valid for the
+        // lifetime of the `PersistableBundle`. The pointer returned by `key.as_ptr()` is guaranteed
+        // to be valid for the duration of this call. The value pointer must be valid because it
+        // comes from a reference, and `string_allocator`/`context` fulfil the `stringAllocator`
+        // contract.
+        match unsafe {
+            APersistableBundle_getString(
+                self.0.as_ptr(),
+                key.as_ptr(),
+                &mut out_value,
+                string_allocator,
+                context,
+            )
+        } {
+            APERSISTABLEBUNDLE_KEY_NOT_FOUND => Ok(None),
+            _ => Ok(Some(buffer_into_string(
+                buffers.pop().expect("string_allocator was not called"),
+            ))),
+        }
+    }
+
+    /// Gets the vector of `T` associated with the given key.
+    ///
+    /// Returns an error if the key contains a NUL character or if allocating the buffer to hold
+    /// the result fails, or `Ok(None)` if the key doesn't exist in the bundle.
     ///
     /// `get_func` should be one of the `APersistableBundle_get*Vector` functions from
     /// `binder_ndk_sys`.
@@ -397,7 +687,7 @@ impl PersistableBundle {
             *mut T,
             i32,
         ) -> i32,
-    ) -> Result<Option<Vec<T>>, NulError> {
+    ) -> Result<Option<Vec<T>>, GetVecError> {
         let key = CString::new(key)?;
         // SAFETY: The wrapped `APersistableBundle` pointer is guaranteed to be valid for the
         // lifetime of the `PersistableBundle`. The pointer returned by `key.as_ptr()` is guaranteed
@@ -405,12 +695,16 @@ impl PersistableBundle {
         match unsafe { get_func(self.0.as_ptr(), key.as_ptr(), null_mut(), 0) } {
             APERSISTABLEBUNDLE_KEY_NOT_FOUND => Ok(None),
             required_buffer_size => {
-                let mut value = vec![
-                    T::default();
-                    usize::try_from(required_buffer_size).expect(
-                        "APersistableBundle_get*Vector returned invalid size"
-                    ) / size_of::<T>()
-                ];
+                let element_count = usize::try_from(required_buffer_size)
+                    .expect("APersistableBundle_get*Vector returned invalid size")
+                    / size_of::<T>();
+                // Reserve the exact capacity fallibly first, so a huge size claimed by a
+                // malicious or corrupt bundle returns an error instead of aborting the process.
+                let mut value = Vec::new();
+                value
+                    .try_reserve_exact(element_count)
+                    .map_err(|_| GetVecError::Alloc(StatusCode::NO_MEMORY))?;
+                value.resize(element_count, T::default());
                 // SAFETY: The wrapped `APersistableBundle` pointer is guaranteed to be valid for
                 // the lifetime of the `PersistableBundle`. The pointer returned by `key.as_ptr()`
                 // is guaranteed to be valid for the lifetime of `key`. The value buffer pointer is
@@ -434,9 +728,9 @@ impl PersistableBundle {
 
     /// Gets the boolean vector value associated with the given key.
     ///
-    /// Returns an error if the key contains a NUL character, or `Ok(None)` if the key doesn't exist
-    /// in the bundle.
-    pub fn get_bool_vec(&self, key: &str) -> Result<Option<Vec<bool>>, NulError> {
+    /// Returns an error if the key contains a NUL character or if allocating the result fails, or
+    /// `Ok(None)` if the key doesn't exist in the bundle.
+    pub fn get_bool_vec(&self, key: &str) -> Result<Option<Vec<bool>>, GetVecError> {
         // SAFETY: APersistableBundle_getBooleanVector fulfils all the safety requirements of
         // `get_vec`.
         unsafe { self.get_vec(key, APersistableBundle_getBooleanVector) }
@@ -444,9 +738,9 @@ impl PersistableBundle {
 
     /// Gets the i32 vector value associated with the given key.
     ///
-    /// Returns an error if the key contains a NUL character, or `Ok(None)` if the key doesn't exist
-    /// in the bundle.
-    pub fn get_int_vec(&self, key: &str) -> Result<Option<Vec<i32>>, NulError> {
+    /// Returns an error if the key contains a NUL character or if allocating the result fails, or
+    /// `Ok(None)` if the key doesn't exist in the bundle.
+    pub fn get_int_vec(&self, key: &str) -> Result<Option<Vec<i32>>, GetVecError> {
         // SAFETY: APersistableBundle_getIntVector fulfils all the safety requirements of
         // `get_vec`.
         unsafe { self.get_vec(key, APersistableBundle_getIntVector) }
@@ -454,9 +748,9 @@ impl PersistableBundle {
 
     /// Gets the i64 vector value associated with the given key.
     ///
-    /// Returns an error if the key contains a NUL character, or `Ok(None)` if the key doesn't exist
-    /// in the bundle.
-    pub fn get_long_vec(&self, key: &str) -> Result<Option<Vec<i64>>, NulError> {
+    /// Returns an error if the key contains a NUL character or if allocating the result fails, or
+    /// `Ok(None)` if the key doesn't exist in the bundle.
+    pub fn get_long_vec(&self, key: &str) -> Result<Option<Vec<i64>>, GetVecError> {
         // SAFETY: APersistableBundle_getLongVector fulfils all the safety requirements of
         // `get_vec`.
         unsafe { self.get_vec(key, APersistableBundle_getLongVector) }
@@ -464,14 +758,57 @@ impl PersistableBundle {
 
     /// Gets the f64 vector value associated with the given key.
     ///
-    /// Returns an error if the key contains a NUL character, or `Ok(None)` if the key doesn't exist
-    /// in the bundle.
-    pub fn get_double_vec(&self, key: &str) -> Result<Option<Vec<f64>>, NulError> {
+    /// Returns an error if the key contains a NUL character or if allocating the result fails, or
+    /// `Ok(None)` if the key doesn't exist in the bundle.
+    pub fn get_double_vec(&self, key: &str) -> Result<Option<Vec<f64>>, GetVecError> {
         // SAFETY: APersistableBundle_getDoubleVector fulfils all the safety requirements of
         // `get_vec`.
         unsafe { self.get_vec(key, APersistableBundle_getDoubleVector) }
     }
 
+    /// Gets the string vector value associated with the given key.
+    ///
+    /// Returns an error if the key contains a NUL character, or `Ok(None)` if the key doesn't exist
+    /// in the bundle.
+    pub fn get_string_vec(&self, key: &str) -> Result<Option<Vec<String>>, NulError> {
+        let key = CString::new(key)?;
+        let mut buffers: Vec<Vec<u8>> = Vec::new();
+        let context = (&mut buffers as *mut Vec<Vec<u8>>).cast::<c_void>();
+        // SAFETY: The wrapped `APersistableBundle` pointer is guaranteed to be valid for the
+        // lifetime of the `PersistableBundle`. The pointer returned by `key.as_ptr()` is guaranteed
+        // to be valid for the duration of this call. A null pointer is allowed for the buffer, to
+        // probe for the required element count.
+        let count = unsafe {
+            APersistableBundle_getStringVector(
+                self.0.as_ptr(),
+                key.as_ptr(),
+                null_mut(),
+                0,
+                string_allocator,
+                context,
+            )
+        };
+        if count == APERSISTABLEBUNDLE_KEY_NOT_FOUND {
+            return Ok(None);
+        }
+        let count =
+            usize::try_from(count).expect("APersistableBundle_getStringVector returned invalid count");
+        let mut out_vector = vec![null_mut::<c_char>(); count];
+        // SAFETY: As above. `out_vector` has room for `count` pointers, which are filled in one by
+        // one by `string_allocator`.
+        unsafe {
+            APersistableBundle_getStringVector(
+                self.0.as_ptr(),
+                key.as_ptr(),
+                out_vector.as_mut_ptr(),
+                count.try_into().unwrap(),
+                string_allocator,
+                context,
+            );
+        }
+        Ok(Some(buffers.into_iter().map(buffer_into_string).collect()))
+    }
+
     /// Gets the `PersistableBundle` value associated with the given key.
     ///
     /// Returns an error if the key contains a NUL character, or `Ok(None)` if the key doesn't exist
@@ -493,6 +830,576 @@ impl PersistableBundle {
             Ok(None)
         }
     }
+
+    /// Calls one of the `APersistableBundle_get*Keys` functions from `binder_ndk_sys`, collecting
+    /// the keys it returns into owned `String`s.
+    ///
+    /// # Safety
+    ///
+    /// `get_func` must be one of the `APersistableBundle_get*Keys` functions. It must allow a null
+    /// `outKeys` pointer, in which case it returns the number of keys without writing anything,
+    /// and otherwise must call the given `stringAllocator` once per key and write the returned
+    /// pointer into `outKeys`.
+    unsafe fn get_keys(
+        &self,
+        get_func: unsafe extern "C" fn(
+            *const APersistableBundle,
+            *mut *mut c_char,
+            i32,
+            extern "C" fn(i32, *mut c_void) -> *mut c_char,
+            *mut c_void,
+        ) -> i32,
+    ) -> Vec<String> {
+        let mut buffers: Vec<Vec<u8>> = Vec::new();
+        let context = (&mut buffers as *mut Vec<Vec<u8>>).cast::<c_void>();
+        // SAFETY: The wrapped `APersistableBundle` pointer is guaranteed to be valid for the
+        // lifetime of the `PersistableBundle`. A null `outKeys` pointer is allowed, to probe for
+        // the number of keys.
+        let count = unsafe { get_func(self.0.as_ptr(), null_mut(), 0, string_allocator, context) };
+        let count =
+            usize::try_from(count).expect("APersistableBundle_get*Keys returned invalid count");
+        if count == 0 {
+            return Vec::new();
+        }
+        let mut out_keys = vec![null_mut::<c_char>(); count];
+        // SAFETY: As above. `out_keys` has room for `count` pointers, which are filled in one by
+        // one by `string_allocator`.
+        unsafe {
+            get_func(
+                self.0.as_ptr(),
+                out_keys.as_mut_ptr(),
+                count.try_into().unwrap(),
+                string_allocator,
+                context,
+            );
+        }
+        buffers.into_iter().map(buffer_into_string).collect()
+    }
+
+    /// Returns the keys present in the bundle, in no particular order.
+    pub fn keys(&self) -> Vec<String> {
+        let get_funcs: [unsafe extern "C" fn(
+            *const APersistableBundle,
+            *mut *mut c_char,
+            i32,
+            extern "C" fn(i32, *mut c_void) -> *mut c_char,
+            *mut c_void,
+        ) -> i32; 11] = [
+            APersistableBundle_getBooleanKeys,
+            APersistableBundle_getIntKeys,
+            APersistableBundle_getLongKeys,
+            APersistableBundle_getDoubleKeys,
+            APersistableBundle_getStringKeys,
+            APersistableBundle_getBooleanVectorKeys,
+            APersistableBundle_getIntVectorKeys,
+            APersistableBundle_getLongVectorKeys,
+            APersistableBundle_getDoubleVectorKeys,
+            APersistableBundle_getStringVectorKeys,
+            APersistableBundle_getPersistableBundleKeys,
+        ];
+        let mut keys = Vec::new();
+        for get_func in get_funcs {
+            // SAFETY: Each of the functions above fulfils the safety requirements of `get_keys`.
+            keys.extend(unsafe { self.get_keys(get_func) });
+        }
+        keys
+    }
+
+    /// Returns the type of value held by the given key, or `None` if it isn't present in the
+    /// bundle.
+    pub fn value_type(&self, key: &str) -> Option<ValueType> {
+        self.get(key).ok().flatten().map(|value| value.value_type())
+    }
+
+    /// Gets the value associated with the given key, regardless of its type.
+    ///
+    /// Returns an error if the key contains a NUL character or an allocation fails, or `Ok(None)`
+    /// if the key doesn't exist in the bundle.
+    pub fn get(&self, key: &str) -> Result<Option<Value>, GetVecError> {
+        if let Some(value) = self.get_bool(key)? {
+            Ok(Some(Value::Bool(value)))
+        } else if let Some(value) = self.get_int(key)? {
+            Ok(Some(Value::Int(value)))
+        } else if let Some(value) = self.get_long(key)? {
+            Ok(Some(Value::Long(value)))
+        } else if let Some(value) = self.get_double(key)? {
+            Ok(Some(Value::Double(value)))
+        } else if let Some(value) = self.get_string(key)? {
+            Ok(Some(Value::String(value)))
+        } else if let Some(value) = self.get_bool_vec(key)? {
+            Ok(Some(Value::BoolVec(value)))
+        } else if let Some(value) = self.get_int_vec(key)? {
+            Ok(Some(Value::IntVec(value)))
+        } else if let Some(value) = self.get_long_vec(key)? {
+            Ok(Some(Value::LongVec(value)))
+        } else if let Some(value) = self.get_double_vec(key)? {
+            Ok(Some(Value::DoubleVec(value)))
+        } else if let Some(value) = self.get_string_vec(key)? {
+            Ok(Some(Value::StringVec(value)))
+        } else if let Some(value) = self.get_persistable_bundle(key)? {
+            Ok(Some(Value::Bundle(value)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Gets the value associated with the given key, regardless of its type.
+    ///
+    /// This is a synonym for `get`, named to match `insert_value` below.
+    pub fn get_value(&self, key: &str) -> Result<Option<Value>, GetVecError> {
+        self.get(key)
+    }
+
+    /// Inserts a type-erased value into the bundle.
+    ///
+    /// If the key is already present then its value will be overwritten by the given value.
+    ///
+    /// Returns an error if the key contains a NUL character.
+    pub fn insert(&mut self, key: &str, value: impl Into<Value>) -> Result<(), NulError> {
+        match value.into() {
+            Value::Bool(value) => self.insert_bool(key, value),
+            Value::Int(value) => self.insert_int(key, value),
+            Value::Long(value) => self.insert_long(key, value),
+            Value::Double(value) => self.insert_double(key, value),
+            Value::String(value) => self.insert_string(key, &value),
+            Value::BoolVec(value) => self.insert_bool_vec(key, &value),
+            Value::IntVec(value) => self.insert_int_vec(key, &value),
+            Value::LongVec(value) => self.insert_long_vec(key, &value),
+            Value::DoubleVec(value) => self.insert_double_vec(key, &value),
+            Value::StringVec(value) => self.insert_string_vec(key, &value),
+            Value::Bundle(value) => self.insert_persistable_bundle(key, &value),
+        }
+    }
+
+    /// Inserts a type-erased value into the bundle.
+    ///
+    /// This is a synonym for `insert`, named to match `get_value` above.
+    pub fn insert_value(&mut self, key: &str, value: impl Into<Value>) -> Result<(), NulError> {
+        self.insert(key, value)
+    }
+
+    /// Returns an `Entry` for the given key, allowing its value to be inspected or lazily
+    /// inserted.
+    pub fn entry(&mut self, key: &str) -> Entry<'_> {
+        Entry { bundle: self, key: key.to_string() }
+    }
+
+    /// Returns an iterator over the `(key, value)` pairs held by the bundle, in no particular
+    /// order.
+    pub fn iter(&self) -> std::vec::IntoIter<(String, Value)> {
+        self.keys()
+            .into_iter()
+            .filter_map(|key| {
+                let value = self.get(&key).ok().flatten()?;
+                Some((key, value))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Merges every entry from `other` into `self`.
+    ///
+    /// If both bundles hold a nested `PersistableBundle` under the same key, the two nested
+    /// bundles are merged recursively instead of one replacing the other. Otherwise, conflicting
+    /// keys are resolved according to `policy`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if inserting a merged value back into the bundle fails, which is not expected since
+    /// the key and value both already came from a valid bundle.
+    pub fn merge_from(&mut self, other: &PersistableBundle, policy: MergePolicy) {
+        for (key, other_value) in other {
+            let self_value = self.get(&key).expect("Failed to read existing value");
+            match (self_value, other_value) {
+                (Some(Value::Bundle(mut nested_self)), Value::Bundle(nested_other)) => {
+                    nested_self.merge_from(&nested_other, policy);
+                    self.insert(&key, nested_self).expect("Failed to insert merged bundle");
+                }
+                (Some(_), other_value) => {
+                    if policy == MergePolicy::Overwrite {
+                        self.insert(&key, other_value).expect("Failed to insert merged value");
+                    }
+                }
+                (None, other_value) => {
+                    self.insert(&key, other_value).expect("Failed to insert merged value");
+                }
+            }
+        }
+    }
+
+    /// Returns a new bundle formed by merging `other` on top of `self`, without modifying either.
+    ///
+    /// See `merge_from` for the merge semantics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if duplicating `self` or inserting a merged value fails.
+    pub fn overlay(&self, other: &PersistableBundle, policy: MergePolicy) -> Self {
+        let mut merged = self.clone();
+        merged.merge_from(other, policy);
+        merged
+    }
+
+    /// Builds a new bundle from an iterator of key-value pairs.
+    ///
+    /// This is a fallible counterpart to `FromIterator`, for use when a key might contain a NUL
+    /// character or allocation might fail.
+    pub fn try_from_iter<I: IntoIterator<Item = (String, Value)>>(
+        iter: I,
+    ) -> Result<Self, StatusCode> {
+        let mut bundle = Self::try_new()?;
+        bundle.try_extend(iter)?;
+        Ok(bundle)
+    }
+
+    /// Inserts every key-value pair from the given iterator into this bundle.
+    ///
+    /// This is a fallible counterpart to `Extend`, for use when a key might contain a NUL
+    /// character.
+    pub fn try_extend<I: IntoIterator<Item = (String, Value)>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), StatusCode> {
+        for (key, value) in iter {
+            self.insert(&key, value).map_err(|_| StatusCode::BAD_VALUE)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the boolean value associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_bool_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> bool,
+    ) -> Result<bool, NulError> {
+        if let Some(value) = self.get_bool(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_bool(key, value)?;
+        Ok(value)
+    }
+
+    /// Returns the integer value associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_int_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> i32,
+    ) -> Result<i32, NulError> {
+        if let Some(value) = self.get_int(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_int(key, value)?;
+        Ok(value)
+    }
+
+    /// Returns the long value associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_long_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> i64,
+    ) -> Result<i64, NulError> {
+        if let Some(value) = self.get_long(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_long(key, value)?;
+        Ok(value)
+    }
+
+    /// Returns the double value associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_double_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> f64,
+    ) -> Result<f64, NulError> {
+        if let Some(value) = self.get_double(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_double(key, value)?;
+        Ok(value)
+    }
+
+    /// Returns the string value associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_string_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> String,
+    ) -> Result<String, NulError> {
+        if let Some(value) = self.get_string(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_string(key, &value)?;
+        Ok(value)
+    }
+
+    /// Returns the boolean vector associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_bool_vec_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> Vec<bool>,
+    ) -> Result<Vec<bool>, GetVecError> {
+        if let Some(value) = self.get_bool_vec(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_bool_vec(key, &value)?;
+        Ok(value)
+    }
+
+    /// Returns the integer vector associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_int_vec_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> Vec<i32>,
+    ) -> Result<Vec<i32>, GetVecError> {
+        if let Some(value) = self.get_int_vec(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_int_vec(key, &value)?;
+        Ok(value)
+    }
+
+    /// Returns the long vector associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_long_vec_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> Vec<i64>,
+    ) -> Result<Vec<i64>, GetVecError> {
+        if let Some(value) = self.get_long_vec(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_long_vec(key, &value)?;
+        Ok(value)
+    }
+
+    /// Returns the double vector associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_double_vec_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> Vec<f64>,
+    ) -> Result<Vec<f64>, GetVecError> {
+        if let Some(value) = self.get_double_vec(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_double_vec(key, &value)?;
+        Ok(value)
+    }
+
+    /// Returns the string vector associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_string_vec_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> Vec<String>,
+    ) -> Result<Vec<String>, NulError> {
+        if let Some(value) = self.get_string_vec(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_string_vec(key, &value)?;
+        Ok(value)
+    }
+
+    /// Returns the nested bundle associated with the given key if it is present, or otherwise
+    /// computes `default`, inserts it and returns it.
+    ///
+    /// If the key is present with some other type then it is overwritten by `default`.
+    pub fn get_persistable_bundle_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> PersistableBundle,
+    ) -> Result<PersistableBundle, NulError> {
+        if let Some(value) = self.get_persistable_bundle(key)? {
+            return Ok(value);
+        }
+        let value = default();
+        self.insert_persistable_bundle(key, &value)?;
+        Ok(value)
+    }
+
+    /// Serializes this bundle into a deterministic, canonical byte representation, returning an
+    /// error if reading a value fails to allocate.
+    ///
+    /// Keys are walked in sorted order (via the key-enumeration API) and each `(key, value)` pair
+    /// is encoded as a length-prefixed key followed by a type-tagged, type-specific payload.
+    ///
+    /// Unlike `write_to_parcel`, which produces a wire format that is only guaranteed to be
+    /// understood by this process, `to_canonical_bytes` is stable across processes and is
+    /// suitable for hashing, diffing or content-addressed caching. It is also the basis of this
+    /// type's `Ord` and `PartialEq` implementations.
+    pub fn try_to_canonical_bytes(&self) -> Result<Vec<u8>, GetVecError> {
+        let mut keys = self.keys();
+        keys.sort();
+
+        let mut bytes = Vec::new();
+        for key in keys {
+            let value = self.get_value(&key)?.expect("Key returned by `keys` was missing");
+            encode_len_prefixed(&mut bytes, key.as_bytes());
+            encode_value(&mut bytes, &value);
+        }
+        Ok(bytes)
+    }
+
+    /// Serializes this bundle into a deterministic, canonical byte representation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reading a value fails to allocate. Use `try_to_canonical_bytes` to handle that
+    /// case instead.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        self.try_to_canonical_bytes().expect("Failed to allocate while reading bundle value")
+    }
+}
+
+impl PartialOrd for PersistableBundle {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PersistableBundle {
+    /// Compares bundles by their `to_canonical_bytes` representation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if reading a value fails to allocate. Compare `try_to_canonical_bytes` results
+    /// directly to handle that case instead.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_canonical_bytes().cmp(&other.to_canonical_bytes())
+    }
+}
+
+impl FromIterator<(String, Value)> for PersistableBundle {
+    /// # Panics
+    ///
+    /// Panics if a key contains a NUL character. Use `try_from_iter` to handle that case instead.
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        let mut bundle = Self::new();
+        bundle.extend(iter);
+        bundle
+    }
+}
+
+impl Extend<(String, Value)> for PersistableBundle {
+    /// # Panics
+    ///
+    /// Panics if a key contains a NUL character. Use `try_extend` to handle that case instead.
+    fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(&key, value).unwrap_or_else(|e| panic!("Invalid key {key:?}: {e}"));
+        }
+    }
+}
+
+impl From<BTreeMap<String, Value>> for PersistableBundle {
+    fn from(map: BTreeMap<String, Value>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl From<HashMap<String, Value>> for PersistableBundle {
+    fn from(map: HashMap<String, Value>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl TryFrom<PersistableBundle> for BTreeMap<String, Value> {
+    type Error = GetVecError;
+
+    fn try_from(bundle: PersistableBundle) -> Result<Self, Self::Error> {
+        bundle
+            .keys()
+            .into_iter()
+            .map(|key| {
+                let value = bundle.get_value(&key)?.expect("Key returned by `keys` was missing");
+                Ok((key, value))
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<PersistableBundle> for HashMap<String, Value> {
+    type Error = GetVecError;
+
+    fn try_from(bundle: PersistableBundle) -> Result<Self, Self::Error> {
+        bundle
+            .keys()
+            .into_iter()
+            .map(|key| {
+                let value = bundle.get_value(&key)?.expect("Key returned by `keys` was missing");
+                Ok((key, value))
+            })
+            .collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a PersistableBundle {
+    type Item = (String, Value);
+    type IntoIter = std::vec::IntoIter<(String, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A view into a single key of a `PersistableBundle`, returned by `PersistableBundle::entry`.
+pub struct Entry<'a> {
+    bundle: &'a mut PersistableBundle,
+    key: String,
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the value already present at this entry's key, or computes `default`, inserts it
+    /// and returns it if there wasn't one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key contains a NUL character, or if reading the existing value fails to
+    /// allocate.
+    pub fn or_insert_with(self, default: impl FnOnce() -> Value) -> Value {
+        if let Some(value) = self.bundle.get(&self.key).expect("Failed to read existing value") {
+            return value;
+        }
+        let value = default();
+        self.bundle.insert(&self.key, value.clone()).expect("Key contained NUL character");
+        value
+    }
 }
 
 // SAFETY: The underlying *APersistableBundle can be moved between threads.
@@ -517,22 +1424,31 @@ impl Drop for PersistableBundle {
 }
 
 impl Clone for PersistableBundle {
+    /// # Panics
+    ///
+    /// Panics if allocation fails. Use `try_clone` to handle that case instead.
     fn clone(&self) -> Self {
-        // SAFETY: The wrapped `APersistableBundle` pointer is guaranteed to be valid for the
-        // lifetime of the `PersistableBundle`.
-        let duplicate = unsafe { APersistableBundle_dup(self.0.as_ptr()) };
-        Self(NonNull::new(duplicate).expect("Duplicated APersistableBundle was null"))
+        self.try_clone().expect("Duplicated APersistableBundle was null")
     }
 }
 
 impl PartialEq for PersistableBundle {
+    /// Compares bundles by their `to_canonical_bytes` representation, so that this agrees with
+    /// the `Ord` implementation (in particular, unlike IEEE `==`, `-0.0` and `+0.0` compare equal
+    /// here only if they are bitwise identical, and a bundle holding `f64::NAN` is equal to
+    /// itself).
+    ///
+    /// # Panics
+    ///
+    /// Panics if reading a value fails to allocate. Compare `try_to_canonical_bytes` results
+    /// directly to handle that case instead.
     fn eq(&self, other: &Self) -> bool {
-        // SAFETY: The wrapped `APersistableBundle` pointers are guaranteed to be valid for the
-        // lifetime of the `PersistableBundle`s.
-        unsafe { APersistableBundle_isEqual(self.0.as_ptr(), other.0.as_ptr()) }
+        self.to_canonical_bytes() == other.to_canonical_bytes()
     }
 }
 
+impl Eq for PersistableBundle {}
+
 impl UnstructuredParcelable for PersistableBundle {
     fn write_to_parcel(&self, parcel: &mut BorrowedParcel) -> Result<(), StatusCode> {
         let status =
@@ -578,6 +1494,13 @@ mod test {
         assert_eq!(bundle, duplicate);
     }
 
+    #[test]
+    fn try_new_try_clone() {
+        let bundle = PersistableBundle::try_new().unwrap();
+        let duplicate = bundle.try_clone().unwrap();
+        assert_eq!(bundle, duplicate);
+    }
+
     #[test]
     fn get_empty() {
         let bundle = PersistableBundle::new();
@@ -688,4 +1611,280 @@ mod test {
 
         assert_eq!(bundle.get_persistable_bundle("bundle"), Ok(Some(sub_bundle)));
     }
+
+    #[test]
+    fn get_string() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.get_string("string"), Ok(None));
+        assert_eq!(bundle.insert_string("string", "foo"), Ok(()));
+        assert_eq!(bundle.get_string("string"), Ok(Some("foo".to_string())));
+    }
+
+    #[test]
+    fn get_string_vec() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.get_string_vec("string"), Ok(None));
+        assert_eq!(bundle.insert_string_vec("string", &["foo", "bar", "baz"]), Ok(()));
+        assert_eq!(
+            bundle.get_string_vec("string"),
+            Ok(Some(vec!["foo".to_string(), "bar".to_string(), "baz".to_string()]))
+        );
+    }
+
+    #[test]
+    fn keys_empty() {
+        let bundle = PersistableBundle::new();
+        assert_eq!(bundle.keys(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn keys_and_value_type() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.insert_bool("bool", true), Ok(()));
+        assert_eq!(bundle.insert_int("int", 42), Ok(()));
+        assert_eq!(bundle.insert_string("string", "foo"), Ok(()));
+
+        let mut keys = bundle.keys();
+        keys.sort();
+        assert_eq!(keys, vec!["bool".to_string(), "int".to_string(), "string".to_string()]);
+
+        assert_eq!(bundle.value_type("bool"), Some(ValueType::Bool));
+        assert_eq!(bundle.value_type("int"), Some(ValueType::Int));
+        assert_eq!(bundle.value_type("string"), Some(ValueType::String));
+        assert_eq!(bundle.value_type("missing"), None);
+    }
+
+    #[test]
+    fn iter() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.insert_bool("bool", true), Ok(()));
+        assert_eq!(bundle.insert_int("int", 42), Ok(()));
+
+        let mut entries: Vec<_> = (&bundle).into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            entries,
+            vec![("bool".to_string(), Value::Bool(true)), ("int".to_string(), Value::Int(42))]
+        );
+    }
+
+    #[test]
+    fn get_generic() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.get("bool"), Ok(None));
+        assert_eq!(bundle.insert_bool("bool", true), Ok(()));
+        assert_eq!(bundle.get("bool"), Ok(Some(Value::Bool(true))));
+    }
+
+    #[test]
+    fn insert_generic() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.insert("int", 42), Ok(()));
+        assert_eq!(bundle.get_int("int"), Ok(Some(42)));
+        assert_eq!(bundle.get("int"), Ok(Some(Value::Int(42))));
+    }
+
+    #[test]
+    fn entry_or_insert_with() {
+        let mut bundle = PersistableBundle::new();
+
+        let value = bundle.entry("int").or_insert_with(|| Value::Int(42));
+        assert_eq!(value, Value::Int(42));
+        assert_eq!(bundle.get_int("int"), Ok(Some(42)));
+
+        // A second call shouldn't overwrite the existing value.
+        let value = bundle.entry("int").or_insert_with(|| Value::Int(66));
+        assert_eq!(value, Value::Int(42));
+        assert_eq!(bundle.get_int("int"), Ok(Some(42)));
+    }
+
+    #[test]
+    fn insert_value_get_value() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.insert_value("int", 42), Ok(()));
+        assert_eq!(bundle.get_value("int"), Ok(Some(Value::Int(42))));
+    }
+
+    #[test]
+    fn merge_from_overwrite() {
+        let mut base = PersistableBundle::new();
+        assert_eq!(base.insert_int("kept", 1), Ok(()));
+        assert_eq!(base.insert_int("shared", 1), Ok(()));
+
+        let mut other = PersistableBundle::new();
+        assert_eq!(other.insert_int("shared", 2), Ok(()));
+        assert_eq!(other.insert_int("added", 3), Ok(()));
+
+        base.merge_from(&other, MergePolicy::Overwrite);
+
+        assert_eq!(base.get_int("kept"), Ok(Some(1)));
+        assert_eq!(base.get_int("shared"), Ok(Some(2)));
+        assert_eq!(base.get_int("added"), Ok(Some(3)));
+    }
+
+    #[test]
+    fn merge_from_keep() {
+        let mut base = PersistableBundle::new();
+        assert_eq!(base.insert_int("shared", 1), Ok(()));
+
+        let mut other = PersistableBundle::new();
+        assert_eq!(other.insert_int("shared", 2), Ok(()));
+        assert_eq!(other.insert_int("added", 3), Ok(()));
+
+        base.merge_from(&other, MergePolicy::Keep);
+
+        assert_eq!(base.get_int("shared"), Ok(Some(1)));
+        assert_eq!(base.get_int("added"), Ok(Some(3)));
+    }
+
+    #[test]
+    fn merge_from_recurses_into_nested_bundles() {
+        let mut base_nested = PersistableBundle::new();
+        assert_eq!(base_nested.insert_int("kept", 1), Ok(()));
+        assert_eq!(base_nested.insert_int("shared", 1), Ok(()));
+        let mut base = PersistableBundle::new();
+        assert_eq!(base.insert_persistable_bundle("nested", &base_nested), Ok(()));
+
+        let mut other_nested = PersistableBundle::new();
+        assert_eq!(other_nested.insert_int("shared", 2), Ok(()));
+        let mut other = PersistableBundle::new();
+        assert_eq!(other.insert_persistable_bundle("nested", &other_nested), Ok(()));
+
+        base.merge_from(&other, MergePolicy::Overwrite);
+
+        let merged_nested = base.get_persistable_bundle("nested").unwrap().unwrap();
+        assert_eq!(merged_nested.get_int("kept"), Ok(Some(1)));
+        assert_eq!(merged_nested.get_int("shared"), Ok(Some(2)));
+
+        // The source bundles should be untouched.
+        assert_eq!(other_nested.get_int("kept"), Ok(None));
+    }
+
+    #[test]
+    fn overlay_does_not_modify_either_input() {
+        let mut base = PersistableBundle::new();
+        assert_eq!(base.insert_int("shared", 1), Ok(()));
+
+        let mut other = PersistableBundle::new();
+        assert_eq!(other.insert_int("shared", 2), Ok(()));
+
+        let merged = base.overlay(&other, MergePolicy::Overwrite);
+
+        assert_eq!(merged.get_int("shared"), Ok(Some(2)));
+        assert_eq!(base.get_int("shared"), Ok(Some(1)));
+        assert_eq!(other.get_int("shared"), Ok(Some(2)));
+    }
+
+    #[test]
+    fn collect_from_pairs() {
+        let bundle: PersistableBundle =
+            [("int".to_string(), Value::Int(42)), ("bool".to_string(), Value::Bool(true))]
+                .into_iter()
+                .collect();
+
+        assert_eq!(bundle.get_int("int"), Ok(Some(42)));
+        assert_eq!(bundle.get_bool("bool"), Ok(Some(true)));
+    }
+
+    #[test]
+    fn extend_from_pairs() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.insert_int("int", 1), Ok(()));
+        bundle.extend([("bool".to_string(), Value::Bool(true))]);
+
+        assert_eq!(bundle.get_int("int"), Ok(Some(1)));
+        assert_eq!(bundle.get_bool("bool"), Ok(Some(true)));
+    }
+
+    #[test]
+    fn from_btree_map() {
+        let map =
+            BTreeMap::from([("int".to_string(), Value::Int(42)), ("bool".to_string(), Value::Bool(true))]);
+        let bundle = PersistableBundle::from(map);
+
+        assert_eq!(bundle.get_int("int"), Ok(Some(42)));
+        assert_eq!(bundle.get_bool("bool"), Ok(Some(true)));
+    }
+
+    #[test]
+    fn try_into_btree_map() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.insert_int("int", 42), Ok(()));
+        assert_eq!(bundle.insert_bool("bool", true), Ok(()));
+
+        let map = BTreeMap::try_from(bundle).unwrap();
+
+        assert_eq!(
+            map,
+            BTreeMap::from([
+                ("int".to_string(), Value::Int(42)),
+                ("bool".to_string(), Value::Bool(true))
+            ])
+        );
+    }
+
+    #[test]
+    fn get_int_or_insert_with_computes_default_once() {
+        let mut bundle = PersistableBundle::new();
+        let mut calls = 0;
+
+        let value = bundle.get_int_or_insert_with("int", || {
+            calls += 1;
+            42
+        });
+        assert_eq!(value, Ok(42));
+        assert_eq!(bundle.get_int("int"), Ok(Some(42)));
+
+        let value = bundle.get_int_or_insert_with("int", || {
+            calls += 1;
+            66
+        });
+        assert_eq!(value, Ok(42));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn get_int_or_insert_with_overwrites_wrong_type() {
+        let mut bundle = PersistableBundle::new();
+        assert_eq!(bundle.insert_bool("key", true), Ok(()));
+
+        assert_eq!(bundle.get_int_or_insert_with("key", || 42), Ok(42));
+        assert_eq!(bundle.get_int("key"), Ok(Some(42)));
+    }
+
+    #[test]
+    fn to_canonical_bytes_is_order_independent() {
+        let mut a = PersistableBundle::new();
+        assert_eq!(a.insert_int("b", 2), Ok(()));
+        assert_eq!(a.insert_int("a", 1), Ok(()));
+
+        let mut b = PersistableBundle::new();
+        assert_eq!(b.insert_int("a", 1), Ok(()));
+        assert_eq!(b.insert_int("b", 2), Ok(()));
+
+        assert_eq!(a.to_canonical_bytes(), b.to_canonical_bytes());
+    }
+
+    #[test]
+    fn to_canonical_bytes_differs_for_different_contents() {
+        let mut a = PersistableBundle::new();
+        assert_eq!(a.insert_int("a", 1), Ok(()));
+
+        let mut b = PersistableBundle::new();
+        assert_eq!(b.insert_int("a", 2), Ok(()));
+
+        assert_ne!(a.to_canonical_bytes(), b.to_canonical_bytes());
+    }
+
+    #[test]
+    fn ord_matches_canonical_bytes() {
+        let mut a = PersistableBundle::new();
+        assert_eq!(a.insert_int("a", 1), Ok(()));
+
+        let mut b = PersistableBundle::new();
+        assert_eq!(b.insert_int("a", 2), Ok(()));
+
+        assert_eq!(a.cmp(&b), a.to_canonical_bytes().cmp(&b.to_canonical_bytes()));
+        assert!(a < b);
+    }
 }
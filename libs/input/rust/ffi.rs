@@ -0,0 +1,27 @@
+/*
+ * Copyright 2023 The Android Open Source Project
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Plain-old-data types shared across the C++/Rust FFI boundary with `InputVerifier.cpp`.
+
+/// The subset of `PointerProperties` and `PointerCoords` that the verifier needs, passed across
+/// the FFI boundary from `InputVerifier.cpp::processMovement`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RustPointerProperties {
+    pub id: i32,
+    pub x: f32,
+    pub y: f32,
+}
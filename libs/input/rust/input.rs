@@ -0,0 +1,214 @@
+/*
+ * Copyright 2023 The Android Open Source Project
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Basic types shared by the input Rust components, mirroring the subset of
+//! `frameworks/native/include/input` that has been ported to Rust so far.
+
+use std::fmt;
+
+/// The ID of an InputDevice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DeviceId(pub i32);
+
+/// The class of the source of an input event, used to group sources that share verification
+/// rules (see `AINPUT_SOURCE_CLASS_*` in the NDK headers).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceClass {
+    Button,
+    Pointer,
+    Navigation,
+    Position,
+    Joystick,
+}
+
+/// The source of an input event, i.e. the kind of device that produced it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Keyboard,
+    Dpad,
+    Gamepad,
+    Touchscreen,
+    Mouse,
+    Stylus,
+    BluetoothStylus,
+    Trackball,
+    MouseRelative,
+    Touchpad,
+    TouchNavigation,
+    Joystick,
+    Rotaryencoder,
+    HdmiRemote,
+}
+
+impl Source {
+    /// Returns whether this source belongs to the given `SourceClass`.
+    pub fn is_from_class(&self, source_class: SourceClass) -> bool {
+        matches!(
+            (self, source_class),
+            (Source::Touchscreen | Source::Stylus | Source::BluetoothStylus, SourceClass::Pointer)
+                | (Source::Mouse, SourceClass::Pointer)
+                | (Source::Touchpad, SourceClass::Pointer)
+                | (Source::Keyboard, SourceClass::Button)
+                | (Source::Dpad | Source::Gamepad, SourceClass::Button)
+                | (Source::Trackball, SourceClass::Navigation)
+                | (Source::TouchNavigation, SourceClass::Navigation)
+                | (Source::Joystick, SourceClass::Joystick)
+        )
+    }
+}
+
+bitflags::bitflags! {
+    /// The buttons that can be reported in a motion event's action button / button state fields.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct MotionButton: u32 {
+        const Primary = input_bindgen::AMOTION_EVENT_BUTTON_PRIMARY;
+        const Secondary = input_bindgen::AMOTION_EVENT_BUTTON_SECONDARY;
+        const Tertiary = input_bindgen::AMOTION_EVENT_BUTTON_TERTIARY;
+        const Back = input_bindgen::AMOTION_EVENT_BUTTON_BACK;
+        const Forward = input_bindgen::AMOTION_EVENT_BUTTON_FORWARD;
+        const StylusPrimary = input_bindgen::AMOTION_EVENT_BUTTON_STYLUS_PRIMARY;
+        const StylusSecondary = input_bindgen::AMOTION_EVENT_BUTTON_STYLUS_SECONDARY;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags that can be set on a motion event.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct MotionFlags: u32 {
+        const CANCELED = input_bindgen::AMOTION_EVENT_FLAG_CANCELED;
+    }
+}
+
+/// The action of a motion event, decoded from the combined action/action-index field of a
+/// `MotionEvent`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MotionAction {
+    Down,
+    Up,
+    Move,
+    Cancel,
+    Outside,
+    PointerDown { action_index: usize },
+    PointerUp { action_index: usize },
+    HoverMove,
+    Scroll,
+    HoverEnter,
+    HoverExit,
+    HoverPointerDown { action_index: usize },
+    HoverPointerExit { action_index: usize },
+    ButtonPress,
+    ButtonRelease,
+}
+
+impl From<u32> for MotionAction {
+    fn from(action: u32) -> Self {
+        let action_masked = action & input_bindgen::AMOTION_EVENT_ACTION_MASK;
+        let action_index = ((action & input_bindgen::AMOTION_EVENT_ACTION_POINTER_INDEX_MASK)
+            >> input_bindgen::AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT)
+            as usize;
+        match action_masked {
+            input_bindgen::AMOTION_EVENT_ACTION_DOWN => Self::Down,
+            input_bindgen::AMOTION_EVENT_ACTION_UP => Self::Up,
+            input_bindgen::AMOTION_EVENT_ACTION_MOVE => Self::Move,
+            input_bindgen::AMOTION_EVENT_ACTION_CANCEL => Self::Cancel,
+            input_bindgen::AMOTION_EVENT_ACTION_OUTSIDE => Self::Outside,
+            input_bindgen::AMOTION_EVENT_ACTION_POINTER_DOWN => Self::PointerDown { action_index },
+            input_bindgen::AMOTION_EVENT_ACTION_POINTER_UP => Self::PointerUp { action_index },
+            input_bindgen::AMOTION_EVENT_ACTION_HOVER_MOVE => Self::HoverMove,
+            input_bindgen::AMOTION_EVENT_ACTION_SCROLL => Self::Scroll,
+            input_bindgen::AMOTION_EVENT_ACTION_HOVER_ENTER => Self::HoverEnter,
+            input_bindgen::AMOTION_EVENT_ACTION_HOVER_EXIT => Self::HoverExit,
+            input_bindgen::AMOTION_EVENT_ACTION_HOVER_POINTER_DOWN => {
+                Self::HoverPointerDown { action_index }
+            }
+            input_bindgen::AMOTION_EVENT_ACTION_HOVER_POINTER_EXIT => {
+                Self::HoverPointerExit { action_index }
+            }
+            input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS => Self::ButtonPress,
+            input_bindgen::AMOTION_EVENT_ACTION_BUTTON_RELEASE => Self::ButtonRelease,
+            _ => panic!("Unknown action: {action_masked}"),
+        }
+    }
+}
+
+/// The ID of a key on a keyboard-like device, as defined by the `AKEYCODE_*` constants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyCode(pub i32);
+
+/// The action of a key event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyAction {
+    Down,
+    Up,
+    Multiple,
+}
+
+impl From<u32> for KeyAction {
+    fn from(action: u32) -> Self {
+        match action {
+            input_bindgen::AKEY_EVENT_ACTION_DOWN => Self::Down,
+            input_bindgen::AKEY_EVENT_ACTION_UP => Self::Up,
+            input_bindgen::AKEY_EVENT_ACTION_MULTIPLE => Self::Multiple,
+            _ => panic!("Unknown key action: {action}"),
+        }
+    }
+}
+
+impl fmt::Display for KeyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Down => write!(f, "ACTION_DOWN"),
+            Self::Up => write!(f, "ACTION_UP"),
+            Self::Multiple => write!(f, "ACTION_MULTIPLE"),
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags that can be set on a key event.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct KeyFlags: u32 {
+        const LONG_PRESS = input_bindgen::AKEY_EVENT_FLAG_LONG_PRESS;
+    }
+}
+
+impl fmt::Display for MotionAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Down => write!(f, "ACTION_DOWN"),
+            Self::Up => write!(f, "ACTION_UP"),
+            Self::Move => write!(f, "ACTION_MOVE"),
+            Self::Cancel => write!(f, "ACTION_CANCEL"),
+            Self::Outside => write!(f, "ACTION_OUTSIDE"),
+            Self::PointerDown { action_index } => {
+                write!(f, "ACTION_POINTER_DOWN({action_index})")
+            }
+            Self::PointerUp { action_index } => write!(f, "ACTION_POINTER_UP({action_index})"),
+            Self::HoverMove => write!(f, "ACTION_HOVER_MOVE"),
+            Self::Scroll => write!(f, "ACTION_SCROLL"),
+            Self::HoverEnter => write!(f, "ACTION_HOVER_ENTER"),
+            Self::HoverExit => write!(f, "ACTION_HOVER_EXIT"),
+            Self::HoverPointerDown { action_index } => {
+                write!(f, "ACTION_HOVER_POINTER_DOWN({action_index})")
+            }
+            Self::HoverPointerExit { action_index } => {
+                write!(f, "ACTION_HOVER_POINTER_EXIT({action_index})")
+            }
+            Self::ButtonPress => write!(f, "ACTION_BUTTON_PRESS"),
+            Self::ButtonRelease => write!(f, "ACTION_BUTTON_RELEASE"),
+        }
+    }
+}
@@ -0,0 +1,529 @@
+/*
+ * Copyright 2023 The Android Open Source Project
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Contains the InputSynthesizer, the inverse of InputVerifier: it generates streams of motion
+//! events that are guaranteed to pass `InputVerifier::process_movement`, for use in fuzzing the
+//! dispatcher without having to hand-craft valid event sequences. It also contains a set of
+//! higher-level gesture primitives (`synthesize_tap`, `synthesize_swipe`,
+//! `synthesize_multitouch_pinch`) for turning a single gesture into the concrete, verifier-valid
+//! event stream that represents it, for use in tests and record/replay.
+
+use crate::ffi::RustPointerProperties;
+use crate::input::{DeviceId, MotionAction, MotionButton, MotionFlags, Source};
+
+/// A single synthesized motion event, in the same shape `InputVerifier::process_movement` takes.
+pub type SynthesizedEvent =
+    (MotionAction, MotionButton, Vec<RustPointerProperties>, MotionFlags, MotionButton);
+
+/// A small, seedable, dependency-free PRNG (SplitMix64), good enough to drive the synthesizer's
+/// transition choices deterministically from a seed.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `[0, bound)`. `bound` must be nonzero.
+    fn next_below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// Generates a valid stream of motion events for a single device, mirroring the bookkeeping that
+/// `InputVerifier` does so that every emitted event is guaranteed to pass verification.
+pub struct InputSynthesizer {
+    device_id: DeviceId,
+    source: Source,
+    rng: SplitMix64,
+    touching_pointer_ids: Vec<i32>,
+    hovering_pointer_ids: Vec<i32>,
+    button_state: MotionButton,
+    next_pointer_id: i32,
+}
+
+const ALL_MOUSE_BUTTONS: [MotionButton; 5] = [
+    MotionButton::Primary,
+    MotionButton::Secondary,
+    MotionButton::Tertiary,
+    MotionButton::Back,
+    MotionButton::Forward,
+];
+
+impl InputSynthesizer {
+    /// Creates a new `InputSynthesizer` for the given device, seeded with `seed`.
+    pub fn new(seed: u64, device_id: DeviceId, source: Source) -> Self {
+        Self {
+            device_id,
+            source,
+            rng: SplitMix64(seed),
+            touching_pointer_ids: Vec::new(),
+            hovering_pointer_ids: Vec::new(),
+            button_state: MotionButton::empty(),
+            next_pointer_id: 0,
+        }
+    }
+
+    fn pointer_properties(ids: &[i32]) -> Vec<RustPointerProperties> {
+        ids.iter().map(|&id| RustPointerProperties { id, x: 0.0, y: 0.0 }).collect()
+    }
+
+    fn allocate_pointer_id(&mut self) -> i32 {
+        let id = self.next_pointer_id;
+        self.next_pointer_id += 1;
+        id
+    }
+
+    /// Emits a single `DOWN`, followed by one `BUTTON_PRESS` per button the caller wants to be
+    /// already held, in ascending order, as `InputVerifier` requires.
+    fn synthesize_down(&mut self, events: &mut Vec<SynthesizedEvent>, pending_buttons: MotionButton) {
+        let id = self.allocate_pointer_id();
+        self.touching_pointer_ids.push(id);
+        events.push((
+            MotionAction::Down,
+            MotionButton::empty(),
+            Self::pointer_properties(&self.touching_pointer_ids),
+            MotionFlags::empty(),
+            pending_buttons,
+        ));
+        for button in ALL_MOUSE_BUTTONS {
+            if pending_buttons.contains(button) {
+                self.button_state |= button;
+                events.push((
+                    MotionAction::ButtonPress,
+                    button,
+                    Self::pointer_properties(&self.touching_pointer_ids),
+                    MotionFlags::empty(),
+                    self.button_state,
+                ));
+            }
+        }
+    }
+
+    /// Generates a single valid motion event stream (from an empty state back to an empty state)
+    /// for this synthesizer's device, with at most `max_steps` events in the middle of the
+    /// gesture.
+    pub fn synthesize_stream(&mut self, max_steps: usize) -> Vec<SynthesizedEvent> {
+        let mut events = Vec::new();
+
+        if self.rng.next_below(2) == 0 {
+            let pending_buttons =
+                if self.rng.next_below(2) == 0 { MotionButton::Primary } else { MotionButton::empty() };
+            self.synthesize_down(&mut events, pending_buttons);
+
+            for _ in 0..self.rng.next_below(max_steps.max(1)) {
+                self.synthesize_touch_step(&mut events);
+            }
+
+            self.synthesize_touch_end(&mut events);
+        } else {
+            let id = self.allocate_pointer_id();
+            self.hovering_pointer_ids.push(id);
+            events.push((
+                MotionAction::HoverEnter,
+                MotionButton::empty(),
+                Self::pointer_properties(&self.hovering_pointer_ids),
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            ));
+            for _ in 0..self.rng.next_below(max_steps.max(1)) {
+                events.push((
+                    MotionAction::HoverMove,
+                    MotionButton::empty(),
+                    Self::pointer_properties(&self.hovering_pointer_ids),
+                    MotionFlags::empty(),
+                    MotionButton::empty(),
+                ));
+            }
+            let ids = std::mem::take(&mut self.hovering_pointer_ids);
+            events.push((
+                MotionAction::HoverExit,
+                MotionButton::empty(),
+                Self::pointer_properties(&ids),
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            ));
+        }
+
+        events
+    }
+
+    fn synthesize_touch_step(&mut self, events: &mut Vec<SynthesizedEvent>) {
+        // Never drop the last touching pointer here; ACTION_UP handles that.
+        let choice = self.rng.next_below(4);
+        match choice {
+            0 => {
+                events.push((
+                    MotionAction::Move,
+                    MotionButton::empty(),
+                    Self::pointer_properties(&self.touching_pointer_ids),
+                    MotionFlags::empty(),
+                    self.button_state,
+                ));
+            }
+            1 => {
+                let action_index = self.touching_pointer_ids.len();
+                let id = self.allocate_pointer_id();
+                self.touching_pointer_ids.push(id);
+                events.push((
+                    MotionAction::PointerDown { action_index },
+                    MotionButton::empty(),
+                    Self::pointer_properties(&self.touching_pointer_ids),
+                    MotionFlags::empty(),
+                    self.button_state,
+                ));
+            }
+            2 if self.touching_pointer_ids.len() > 1 => {
+                let action_index = self.rng.next_below(self.touching_pointer_ids.len());
+                events.push((
+                    MotionAction::PointerUp { action_index },
+                    MotionButton::empty(),
+                    Self::pointer_properties(&self.touching_pointer_ids),
+                    MotionFlags::empty(),
+                    self.button_state,
+                ));
+                self.touching_pointer_ids.remove(action_index);
+            }
+            _ => {
+                self.synthesize_button_step(events);
+            }
+        }
+    }
+
+    fn synthesize_button_step(&mut self, events: &mut Vec<SynthesizedEvent>) {
+        let unpressed: Vec<MotionButton> =
+            ALL_MOUSE_BUTTONS.into_iter().filter(|b| !self.button_state.contains(*b)).collect();
+        let pressed: Vec<MotionButton> =
+            ALL_MOUSE_BUTTONS.into_iter().filter(|b| self.button_state.contains(*b)).collect();
+
+        if !pressed.is_empty() && (unpressed.is_empty() || self.rng.next_below(2) == 0) {
+            let button = pressed[self.rng.next_below(pressed.len())];
+            self.button_state -= button;
+            events.push((
+                MotionAction::ButtonRelease,
+                button,
+                Self::pointer_properties(&self.touching_pointer_ids),
+                MotionFlags::empty(),
+                self.button_state,
+            ));
+        } else if !unpressed.is_empty() {
+            let button = unpressed[self.rng.next_below(unpressed.len())];
+            self.button_state |= button;
+            events.push((
+                MotionAction::ButtonPress,
+                button,
+                Self::pointer_properties(&self.touching_pointer_ids),
+                MotionFlags::empty(),
+                self.button_state,
+            ));
+        }
+    }
+
+    fn synthesize_touch_end(&mut self, events: &mut Vec<SynthesizedEvent>) {
+        // BUTTON_RELEASE for any still-pressed buttons before the final UP/CANCEL, as
+        // InputVerifier requires the button state to be empty before the last pointer leaves.
+        for button in ALL_MOUSE_BUTTONS {
+            if self.button_state.contains(button) {
+                self.button_state -= button;
+                events.push((
+                    MotionAction::ButtonRelease,
+                    button,
+                    Self::pointer_properties(&self.touching_pointer_ids),
+                    MotionFlags::empty(),
+                    self.button_state,
+                ));
+            }
+        }
+
+        if self.rng.next_below(8) == 0 {
+            let ids = std::mem::take(&mut self.touching_pointer_ids);
+            events.push((
+                MotionAction::Cancel,
+                MotionButton::empty(),
+                Self::pointer_properties(&ids),
+                MotionFlags::CANCELED,
+                MotionButton::empty(),
+            ));
+            return;
+        }
+
+        while self.touching_pointer_ids.len() > 1 {
+            let action_index = self.rng.next_below(self.touching_pointer_ids.len());
+            events.push((
+                MotionAction::PointerUp { action_index },
+                MotionButton::empty(),
+                Self::pointer_properties(&self.touching_pointer_ids),
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            ));
+            self.touching_pointer_ids.remove(action_index);
+        }
+        let ids = std::mem::take(&mut self.touching_pointer_ids);
+        events.push((
+            MotionAction::Up,
+            MotionButton::empty(),
+            Self::pointer_properties(&ids),
+            MotionFlags::empty(),
+            MotionButton::empty(),
+        ));
+    }
+}
+
+fn pointer_properties_at(ids_and_positions: &[(i32, f32, f32)]) -> Vec<RustPointerProperties> {
+    ids_and_positions.iter().map(|&(id, x, y)| RustPointerProperties { id, x, y }).collect()
+}
+
+/// Synthesizes a tap gesture (`DOWN` immediately followed by `UP`) at `(x, y)`.
+pub fn synthesize_tap(_device_id: DeviceId, _source: Source, x: f32, y: f32) -> Vec<SynthesizedEvent> {
+    let pointer = [(0, x, y)];
+    vec![
+        (
+            MotionAction::Down,
+            MotionButton::empty(),
+            pointer_properties_at(&pointer),
+            MotionFlags::empty(),
+            MotionButton::empty(),
+        ),
+        (
+            MotionAction::Up,
+            MotionButton::empty(),
+            pointer_properties_at(&pointer),
+            MotionFlags::empty(),
+            MotionButton::empty(),
+        ),
+    ]
+}
+
+/// Synthesizes a single-pointer swipe from `from` to `to`, broken up into `steps` evenly-spaced
+/// `MOVE` events between the initial `DOWN` and the final `UP`.
+pub fn synthesize_swipe(
+    _device_id: DeviceId,
+    _source: Source,
+    from: (f32, f32),
+    to: (f32, f32),
+    steps: usize,
+) -> Vec<SynthesizedEvent> {
+    let mut events = Vec::with_capacity(steps + 2);
+    events.push((
+        MotionAction::Down,
+        MotionButton::empty(),
+        pointer_properties_at(&[(0, from.0, from.1)]),
+        MotionFlags::empty(),
+        MotionButton::empty(),
+    ));
+    for step in 1..=steps {
+        let t = step as f32 / (steps + 1) as f32;
+        let x = from.0 + (to.0 - from.0) * t;
+        let y = from.1 + (to.1 - from.1) * t;
+        events.push((
+            MotionAction::Move,
+            MotionButton::empty(),
+            pointer_properties_at(&[(0, x, y)]),
+            MotionFlags::empty(),
+            MotionButton::empty(),
+        ));
+    }
+    events.push((
+        MotionAction::Up,
+        MotionButton::empty(),
+        pointer_properties_at(&[(0, to.0, to.1)]),
+        MotionFlags::empty(),
+        MotionButton::empty(),
+    ));
+    events
+}
+
+/// Synthesizes a multi-finger pinch: a primary `DOWN` for the first pointer, a `POINTER_DOWN` for
+/// each additional pointer, `steps` `MOVE` events interpolating every pointer from its start to
+/// its end position, then `POINTER_UP` for each additional pointer in reverse order, and a final
+/// `UP` for the primary pointer.
+///
+/// `pointers` gives the `(start, end)` position of every finger; `pointers[0]` is the primary
+/// pointer that opens and closes the gesture.
+pub fn synthesize_multitouch_pinch(
+    _device_id: DeviceId,
+    _source: Source,
+    pointers: &[((f32, f32), (f32, f32))],
+    steps: usize,
+) -> Vec<SynthesizedEvent> {
+    assert!(pointers.len() >= 2, "a pinch needs at least two pointers");
+    let mut events = Vec::new();
+    let mut current: Vec<(i32, f32, f32)> =
+        vec![(0, pointers[0].0 .0, pointers[0].0 .1)];
+
+    events.push((
+        MotionAction::Down,
+        MotionButton::empty(),
+        pointer_properties_at(&current),
+        MotionFlags::empty(),
+        MotionButton::empty(),
+    ));
+
+    for (id, &(start, _end)) in pointers.iter().enumerate().skip(1) {
+        let action_index = current.len();
+        current.push((id as i32, start.0, start.1));
+        events.push((
+            MotionAction::PointerDown { action_index },
+            MotionButton::empty(),
+            pointer_properties_at(&current),
+            MotionFlags::empty(),
+            MotionButton::empty(),
+        ));
+    }
+
+    for step in 1..=steps {
+        let t = step as f32 / (steps + 1) as f32;
+        for (i, &(start, end)) in pointers.iter().enumerate() {
+            current[i].1 = start.0 + (end.0 - start.0) * t;
+            current[i].2 = start.1 + (end.1 - start.1) * t;
+        }
+        events.push((
+            MotionAction::Move,
+            MotionButton::empty(),
+            pointer_properties_at(&current),
+            MotionFlags::empty(),
+            MotionButton::empty(),
+        ));
+    }
+
+    for action_index in (1..pointers.len()).rev() {
+        events.push((
+            MotionAction::PointerUp { action_index },
+            MotionButton::empty(),
+            pointer_properties_at(&current),
+            MotionFlags::empty(),
+            MotionButton::empty(),
+        ));
+        current.remove(action_index);
+    }
+
+    let (_, end) = pointers[0];
+    current[0] = (0, end.0, end.1);
+    events.push((
+        MotionAction::Up,
+        MotionButton::empty(),
+        pointer_properties_at(&current),
+        MotionFlags::empty(),
+        MotionButton::empty(),
+    ));
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_verifier::InputVerifier;
+    use input_bindgen::AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT;
+
+    fn action_to_u32(action: MotionAction) -> u32 {
+        match action {
+            MotionAction::Down => input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+            MotionAction::Up => input_bindgen::AMOTION_EVENT_ACTION_UP,
+            MotionAction::Move => input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+            MotionAction::Cancel => input_bindgen::AMOTION_EVENT_ACTION_CANCEL,
+            MotionAction::HoverEnter => input_bindgen::AMOTION_EVENT_ACTION_HOVER_ENTER,
+            MotionAction::HoverMove => input_bindgen::AMOTION_EVENT_ACTION_HOVER_MOVE,
+            MotionAction::HoverExit => input_bindgen::AMOTION_EVENT_ACTION_HOVER_EXIT,
+            MotionAction::ButtonPress => input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+            MotionAction::ButtonRelease => input_bindgen::AMOTION_EVENT_ACTION_BUTTON_RELEASE,
+            MotionAction::PointerDown { action_index } => {
+                input_bindgen::AMOTION_EVENT_ACTION_POINTER_DOWN
+                    | ((action_index as u32) << AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT)
+            }
+            MotionAction::PointerUp { action_index } => {
+                input_bindgen::AMOTION_EVENT_ACTION_POINTER_UP
+                    | ((action_index as u32) << AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT)
+            }
+            other => panic!("Unexpected synthesized action: {other}"),
+        }
+    }
+
+    #[test]
+    fn synthesized_streams_always_verify() {
+        for seed in 0..200 {
+            let mut synthesizer = InputSynthesizer::new(seed, DeviceId(1), Source::Mouse);
+            let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+            let stream = synthesizer.synthesize_stream(5);
+            for (action, action_button, pointer_properties, flags, button_state) in stream {
+                verifier
+                    .process_movement(
+                        DeviceId(1),
+                        Source::Mouse,
+                        action_to_u32(action),
+                        action_button,
+                        &pointer_properties,
+                        flags,
+                        button_state,
+                    )
+                    .unwrap_or_else(|e| panic!("seed {seed} produced invalid stream: {e}"));
+            }
+        }
+    }
+
+    fn assert_stream_verifies(device_id: DeviceId, source: Source, stream: Vec<SynthesizedEvent>) {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        for (action, action_button, pointer_properties, flags, button_state) in stream {
+            verifier
+                .process_movement(
+                    device_id,
+                    source,
+                    action_to_u32(action),
+                    action_button,
+                    &pointer_properties,
+                    flags,
+                    button_state,
+                )
+                .unwrap_or_else(|e| panic!("synthesized stream failed to verify: {e}"));
+        }
+    }
+
+    #[test]
+    fn tap_verifies() {
+        assert_stream_verifies(
+            DeviceId(1),
+            Source::Touchscreen,
+            synthesize_tap(DeviceId(1), Source::Touchscreen, 10.0, 20.0),
+        );
+    }
+
+    #[test]
+    fn swipe_verifies() {
+        assert_stream_verifies(
+            DeviceId(1),
+            Source::Touchscreen,
+            synthesize_swipe(DeviceId(1), Source::Touchscreen, (0.0, 0.0), (100.0, 200.0), 4),
+        );
+    }
+
+    #[test]
+    fn multitouch_pinch_verifies() {
+        assert_stream_verifies(
+            DeviceId(1),
+            Source::Touchscreen,
+            synthesize_multitouch_pinch(
+                DeviceId(1),
+                Source::Touchscreen,
+                &[((50.0, 50.0), (10.0, 10.0)), ((60.0, 60.0), (100.0, 100.0))],
+                4,
+            ),
+        );
+    }
+}
@@ -17,7 +17,10 @@
 //! Contains the InputVerifier, used to validate a stream of input events.
 
 use crate::ffi::RustPointerProperties;
-use crate::input::{DeviceId, MotionAction, MotionButton, MotionFlags, Source, SourceClass};
+use crate::input::{
+    DeviceId, KeyAction, KeyCode, KeyFlags, MotionAction, MotionButton, MotionFlags, Source,
+    SourceClass,
+};
 use log::info;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -41,11 +44,7 @@ fn verify_event(
         ));
     }
     match action {
-        MotionAction::Down
-        | MotionAction::HoverEnter
-        | MotionAction::HoverExit
-        | MotionAction::HoverMove
-        | MotionAction::Up => {
+        MotionAction::Down | MotionAction::HoverEnter | MotionAction::HoverExit | MotionAction::Up => {
             if pointer_count != 1 {
                 return Err(format!(
                     "Invalid {} event: there are {} pointers in the event",
@@ -63,7 +62,10 @@ fn verify_event(
             }
         }
 
-        MotionAction::PointerDown { action_index } | MotionAction::PointerUp { action_index } => {
+        MotionAction::PointerDown { action_index }
+        | MotionAction::PointerUp { action_index }
+        | MotionAction::HoverPointerDown { action_index }
+        | MotionAction::HoverPointerExit { action_index } => {
             if action_index >= pointer_count {
                 return Err(format!("Got {}, but event has {} pointer(s)", action, pointer_count));
             }
@@ -107,6 +109,30 @@ struct ButtonVerifier {
     /// | `BUTTON_PRESS` | `SECONDARY`   | `PRIMARY`, `SECONDARY` |
     /// | `MOVE`         | -             | `PRIMARY`, `SECONDARY` |
     pending_buttons: MotionButton,
+
+    /// The source first observed for this device, used to reject buttons that don't belong to
+    /// that source's button family (e.g. a stylus button bit on a mouse-sourced event).
+    source: Option<Source>,
+}
+
+/// Returns the set of buttons that are valid for the given source, or `None` if this source
+/// doesn't have a restricted button family (in which case no check is performed).
+fn allowed_buttons_for_source(source: Source) -> Option<MotionButton> {
+    match source {
+        // Stylus sources may also report the standard PRIMARY button for tip contact, in
+        // addition to the two dedicated stylus side buttons.
+        Source::Stylus | Source::BluetoothStylus => Some(
+            MotionButton::Primary | MotionButton::StylusPrimary | MotionButton::StylusSecondary,
+        ),
+        Source::Mouse | Source::MouseRelative | Source::Touchpad => Some(
+            MotionButton::Primary
+                | MotionButton::Secondary
+                | MotionButton::Tertiary
+                | MotionButton::Back
+                | MotionButton::Forward,
+        ),
+        _ => None,
+    }
 }
 
 impl ButtonVerifier {
@@ -115,7 +141,21 @@ impl ButtonVerifier {
         action: MotionAction,
         action_button: MotionButton,
         button_state: MotionButton,
+        source: Source,
     ) -> Result<(), String> {
+        let source = *self.source.get_or_insert(source);
+        if let Some(allowed) = allowed_buttons_for_source(source) {
+            if !allowed.contains(action_button) {
+                return Err(format!(
+                    "{action} action button {action_button:?} is not valid for {source:?} source"
+                ));
+            }
+            if !allowed.contains(button_state) {
+                return Err(format!(
+                    "{action} button state {button_state:?} is not valid for {source:?} source"
+                ));
+            }
+        }
         if !self.pending_buttons.is_empty() {
             // We just saw a DOWN with some additional buttons in its state, so it should be
             // immediately followed by ButtonPress events for those buttons.
@@ -174,6 +214,22 @@ impl ButtonVerifier {
     }
 }
 
+/// A corrective event that `resynchronize` determined must be spliced into the dispatched event
+/// stream before the real event, to repair bookkeeping that has drifted out of sync (e.g. because
+/// an earlier event was dropped upstream).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SyntheticEvent {
+    /// An `ACTION_CANCEL` (with `FLAG_CANCELED`) is needed to end a gesture the verifier still
+    /// considers in progress before a new `DOWN` can start one.
+    Cancel,
+    /// An `ACTION_BUTTON_RELEASE` is needed for a button the verifier still considers pressed,
+    /// but that's missing from the incoming event's button state.
+    ButtonRelease { action_button: MotionButton },
+    /// An `ACTION_POINTER_DOWN` is needed for a pointer that appears in a `MOVE` event without
+    /// ever having been introduced by a `DOWN`/`POINTER_DOWN`.
+    PointerDown { action_index: usize },
+}
+
 /// The InputVerifier is used to validate a stream of input events.
 pub struct InputVerifier {
     name: String,
@@ -181,6 +237,24 @@ pub struct InputVerifier {
     touching_pointer_ids_by_device: HashMap<DeviceId, HashSet<i32>>,
     hovering_pointer_ids_by_device: HashMap<DeviceId, HashSet<i32>>,
     button_verifier_by_device: HashMap<DeviceId, ButtonVerifier>,
+    /// The `Source` most recently reported by each device, used to catch a device that suddenly
+    /// reports events from a different source mid-gesture (e.g. a reader bug that flips a touch
+    /// device to report itself as a relative mouse). A different source reported while no gesture
+    /// is in progress replaces the recorded one.
+    device_capabilities_by_device: HashMap<DeviceId, Source>,
+    /// The logical surface extent (width, height) configured for each device, if any. Coordinate
+    /// bounds checking is opt-in: a device with no entry here is not bounds-checked.
+    surface_extent_by_device: HashMap<DeviceId, (f32, f32)>,
+    /// The per-pointer coordinates last seen for each device, used for the opt-in jump check.
+    last_pointer_coords_by_device: HashMap<DeviceId, HashMap<i32, (f32, f32)>>,
+    /// If set, the maximum distance a pointer may move between successive `MOVE` events before
+    /// it is considered an impossible "teleport".
+    pointer_jump_limit: Option<f32>,
+    /// The `repeat_count` of the most recent `ACTION_DOWN` seen for each currently-down key, by
+    /// `(device_id, key_code)`.
+    pressed_keys_by_device: HashMap<(DeviceId, KeyCode), i32>,
+    /// Whether recovery mode is enabled; see `set_recovery_mode`.
+    recovery_mode: bool,
 }
 
 impl InputVerifier {
@@ -197,9 +271,37 @@ impl InputVerifier {
             touching_pointer_ids_by_device: HashMap::new(),
             hovering_pointer_ids_by_device: HashMap::new(),
             button_verifier_by_device: HashMap::new(),
+            device_capabilities_by_device: HashMap::new(),
+            surface_extent_by_device: HashMap::new(),
+            last_pointer_coords_by_device: HashMap::new(),
+            pointer_jump_limit: None,
+            pressed_keys_by_device: HashMap::new(),
+            recovery_mode: false,
         }
     }
 
+    /// Configures the logical surface extent for `device_id`, enabling bounds checking of its
+    /// pointer coordinates. Coordinates outside `[0, width] x [0, height]` will be rejected.
+    pub fn set_surface_extent(&mut self, device_id: DeviceId, width: f32, height: f32) {
+        self.surface_extent_by_device.insert(device_id, (width, height));
+    }
+
+    /// Configures the maximum distance, in the same units as pointer coordinates, that a pointer
+    /// may move in a `MOVE` event from the position it had in the previous event that carried it
+    /// (of any action, e.g. the `DOWN` that introduced it). Exceeding it is rejected as an
+    /// impossible jump. Disabled (the default) when never called.
+    pub fn set_pointer_jump_limit(&mut self, limit: f32) {
+        self.pointer_jump_limit = Some(limit);
+    }
+
+    /// Enables or disables recovery mode. When enabled, `resynchronize` repairs internal
+    /// bookkeeping ahead of an upcoming event and returns the corrective events that must be
+    /// spliced into the dispatched stream to keep it valid. Disabled (the default) when never
+    /// called.
+    pub fn set_recovery_mode(&mut self, enabled: bool) {
+        self.recovery_mode = enabled;
+    }
+
     /// Process a pointer movement event from an InputDevice.
     /// If the event is not valid, we return an error string that describes the issue.
     #[allow(clippy::too_many_arguments)]
@@ -213,6 +315,27 @@ impl InputVerifier {
         flags: MotionFlags,
         button_state: MotionButton,
     ) -> Result<(), String> {
+        match self.device_capabilities_by_device.get(&device_id) {
+            Some(&recorded_source) if recorded_source != source => {
+                let gesture_in_progress = self
+                    .touching_pointer_ids_by_device
+                    .contains_key(&device_id)
+                    || self.hovering_pointer_ids_by_device.contains_key(&device_id);
+                if gesture_in_progress {
+                    return Err(format!(
+                        "{}: device {:?} reported source {:?} mid-gesture, but had previously \
+                         reported source {:?}",
+                        self.name, device_id, source, recorded_source
+                    ));
+                }
+                self.device_capabilities_by_device.insert(device_id, source);
+            }
+            Some(_) => {}
+            None => {
+                self.device_capabilities_by_device.insert(device_id, source);
+            }
+        }
+
         if !source.is_from_class(SourceClass::Pointer) {
             // Skip non-pointer sources like MOUSE_RELATIVE for now
             return Ok(());
@@ -230,10 +353,13 @@ impl InputVerifier {
 
         verify_event(action.into(), action_button, pointer_properties, &flags)?;
 
+        self.verify_pointer_coordinates(device_id, action.into(), pointer_properties, source)?;
+
         self.button_verifier_by_device.entry(device_id).or_default().process_action(
             action.into(),
             action_button,
             button_state,
+            source,
         )?;
 
         match action.into() {
@@ -327,12 +453,6 @@ impl InputVerifier {
                 }
                 self.touching_pointer_ids_by_device.remove(&device_id);
             }
-            /*
-             * The hovering protocol currently supports a single pointer only, because we do not
-             * have ACTION_HOVER_POINTER_ENTER or ACTION_HOVER_POINTER_EXIT.
-             * Still, we are keeping the infrastructure here pretty general in case that is
-             * eventually supported.
-             */
             MotionAction::HoverEnter => {
                 if self.hovering_pointer_ids_by_device.contains_key(&device_id) {
                     return Err(format!(
@@ -344,12 +464,62 @@ impl InputVerifier {
                 let it = self.hovering_pointer_ids_by_device.entry(device_id).or_default();
                 it.insert(pointer_properties[0].id);
             }
+            MotionAction::HoverPointerDown { action_index } => {
+                if !self.hovering_pointer_ids_by_device.contains_key(&device_id) {
+                    return Err(format!(
+                        "{}: Received HOVER_POINTER_DOWN but no pointers are currently hovering \
+                        for device {:?}",
+                        self.name, device_id
+                    ));
+                }
+                let it = self.hovering_pointer_ids_by_device.get_mut(&device_id).unwrap();
+                if it.len() != pointer_properties.len() - 1 {
+                    return Err(format!(
+                        "{}: There are currently {} hovering pointers, but the incoming \
+                         HOVER_POINTER_DOWN event has {}",
+                        self.name,
+                        it.len(),
+                        pointer_properties.len()
+                    ));
+                }
+                let pointer_id = pointer_properties[action_index].id;
+                if it.contains(&pointer_id) {
+                    return Err(format!(
+                        "{}: Hovering pointer with id={} already present found in the properties",
+                        self.name, pointer_id
+                    ));
+                }
+                it.insert(pointer_id);
+            }
             MotionAction::HoverMove => {
                 // For compatibility reasons, we allow HOVER_MOVE without a prior HOVER_ENTER.
                 // If there was no prior HOVER_ENTER, just start a new hovering pointer.
+                if self.hovering_pointer_ids_by_device.contains_key(&device_id)
+                    && !self.ensure_hovering_pointers_match(device_id, pointer_properties)
+                {
+                    return Err(format!("{}: ACTION_HOVER_MOVE hovering pointers don't match", self.name));
+                }
                 let it = self.hovering_pointer_ids_by_device.entry(device_id).or_default();
                 it.insert(pointer_properties[0].id);
             }
+            MotionAction::HoverPointerExit { action_index } => {
+                if !self.ensure_hovering_pointers_match(device_id, pointer_properties) {
+                    return Err(format!(
+                        "{}: ACTION_HOVER_POINTER_EXIT hovering pointers don't match",
+                        self.name
+                    ));
+                }
+                let it = self.hovering_pointer_ids_by_device.get_mut(&device_id).unwrap();
+                let pointer_id = pointer_properties[action_index].id;
+                it.remove(&pointer_id);
+                if it.is_empty() {
+                    return Err(format!(
+                        "{}: Got HOVER_POINTER_EXIT for the last hovering pointer {}; expected \
+                        HOVER_EXIT instead for device {:?}",
+                        self.name, pointer_id, device_id
+                    ));
+                }
+            }
             MotionAction::HoverExit => {
                 if !self.hovering_pointer_ids_by_device.contains_key(&device_id) {
                     return Err(format!(
@@ -375,12 +545,177 @@ impl InputVerifier {
         Ok(())
     }
 
+    /// Process a key event from an InputDevice.
+    /// If the event is not valid, we return an error string that describes the issue.
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_key(
+        &mut self,
+        device_id: DeviceId,
+        _source: Source,
+        action: u32,
+        key_code: KeyCode,
+        _meta_state: u32,
+        _flags: KeyFlags,
+        repeat_count: i32,
+    ) -> Result<(), String> {
+        match action.into() {
+            KeyAction::Down => match self.pressed_keys_by_device.get(&(device_id, key_code)) {
+                None => {
+                    if repeat_count != 0 {
+                        return Err(format!(
+                            "{}: initial ACTION_DOWN for key {:?} on device {:?} must have \
+                             repeat_count 0, but got {}",
+                            self.name, key_code, device_id, repeat_count
+                        ));
+                    }
+                    self.pressed_keys_by_device.insert((device_id, key_code), repeat_count);
+                }
+                Some(&last_repeat_count) => {
+                    if repeat_count <= last_repeat_count {
+                        return Err(format!(
+                            "{}: repeat ACTION_DOWN for key {:?} on device {:?} must have an \
+                             increasing repeat_count, but got {} after {}",
+                            self.name, key_code, device_id, repeat_count, last_repeat_count
+                        ));
+                    }
+                    self.pressed_keys_by_device.insert((device_id, key_code), repeat_count);
+                }
+            },
+            KeyAction::Up => {
+                if self.pressed_keys_by_device.remove(&(device_id, key_code)).is_none() {
+                    return Err(format!(
+                        "{}: ACTION_UP for key {:?} on device {:?} without a preceding \
+                         ACTION_DOWN",
+                        self.name, key_code, device_id
+                    ));
+                }
+            }
+            KeyAction::Multiple => {}
+        }
+        Ok(())
+    }
+
+    /// If recovery mode is enabled (see `set_recovery_mode`), inspects the upcoming event and
+    /// repairs any internal bookkeeping a broken stream has drifted out of, returning the
+    /// corrective `SyntheticEvent`s that must be dispatched, in order, before it. Has no effect,
+    /// and always returns an empty `Vec`, when recovery mode is disabled.
+    ///
+    /// This only repairs this verifier's own bookkeeping; the caller is responsible for actually
+    /// emitting the corresponding events to whatever is downstream of the verifier.
+    pub fn resynchronize(
+        &mut self,
+        device_id: DeviceId,
+        action: u32,
+        pointer_properties: &[RustPointerProperties],
+        button_state: MotionButton,
+    ) -> Vec<SyntheticEvent> {
+        if !self.recovery_mode {
+            return Vec::new();
+        }
+        let action: MotionAction = action.into();
+        let mut synthetic = Vec::new();
+
+        if action == MotionAction::Down
+            && self.touching_pointer_ids_by_device.remove(&device_id).is_some()
+        {
+            synthetic.push(SyntheticEvent::Cancel);
+        }
+
+        if action == MotionAction::Move {
+            if let Some(touching) = self.touching_pointer_ids_by_device.get_mut(&device_id) {
+                for (action_index, pointer) in pointer_properties.iter().enumerate() {
+                    if touching.insert(pointer.id) {
+                        synthetic.push(SyntheticEvent::PointerDown { action_index });
+                    }
+                }
+            }
+        }
+
+        if matches!(action, MotionAction::Up | MotionAction::Down) {
+            if let Some(verifier) = self.button_verifier_by_device.get_mut(&device_id) {
+                let stale = verifier.button_state - button_state;
+                for action_button in stale.iter() {
+                    synthetic.push(SyntheticEvent::ButtonRelease { action_button });
+                }
+                verifier.button_state -= stale;
+            }
+        }
+
+        synthetic
+    }
+
     /// Notify the verifier that the device has been reset, which will cause the verifier to erase
     /// the current internal state for this device. Subsequent events from this device are expected
     //// to start a new gesture.
     pub fn reset_device(&mut self, device_id: DeviceId) {
         self.touching_pointer_ids_by_device.remove(&device_id);
         self.hovering_pointer_ids_by_device.remove(&device_id);
+        self.device_capabilities_by_device.remove(&device_id);
+        self.last_pointer_coords_by_device.remove(&device_id);
+        self.pressed_keys_by_device.retain(|&(id, _), _| id != device_id);
+        self.button_verifier_by_device.remove(&device_id);
+    }
+
+    /// Checks that `pointer_properties` have finite coordinates, and, if coordinate checking is
+    /// enabled for this device, that they fall within its configured surface extent and, for a
+    /// `MOVE`, haven't jumped further than the configured limit since the previous event that
+    /// carried the same pointer. Sources that report
+    /// relative deltas rather than absolute positions (e.g. `MouseRelative`) are exempt from the
+    /// surface extent and jump checks, since "out of bounds" and "teleport" aren't meaningful for
+    /// them.
+    fn verify_pointer_coordinates(
+        &mut self,
+        device_id: DeviceId,
+        action: MotionAction,
+        pointer_properties: &[RustPointerProperties],
+        source: Source,
+    ) -> Result<(), String> {
+        let reports_absolute_position = source != Source::MouseRelative;
+        let extent = if reports_absolute_position {
+            self.surface_extent_by_device.get(&device_id).copied()
+        } else {
+            None
+        };
+        for pointer in pointer_properties {
+            if !pointer.x.is_finite() || !pointer.y.is_finite() {
+                return Err(format!(
+                    "{}: pointer {} has non-finite coordinates ({}, {})",
+                    self.name, pointer.id, pointer.x, pointer.y
+                ));
+            }
+            if let Some((width, height)) = extent {
+                if pointer.x < 0.0 || pointer.x > width || pointer.y < 0.0 || pointer.y > height {
+                    return Err(format!(
+                        "{}: pointer {} coordinates ({}, {}) are outside the device's {}x{} \
+                         surface",
+                        self.name, pointer.id, pointer.x, pointer.y, width, height
+                    ));
+                }
+            }
+        }
+
+        let history = self.last_pointer_coords_by_device.entry(device_id).or_default();
+        if action == MotionAction::Move && reports_absolute_position {
+            if let Some(limit) = self.pointer_jump_limit {
+                for pointer in pointer_properties {
+                    if let Some(&(last_x, last_y)) = history.get(&pointer.id) {
+                        let distance = ((pointer.x - last_x).powi(2) + (pointer.y - last_y).powi(2))
+                            .sqrt();
+                        if distance > limit {
+                            return Err(format!(
+                                "{}: pointer {} jumped {distance} units in a single MOVE, \
+                                 exceeding the {limit} limit",
+                                self.name, pointer.id
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        for pointer in pointer_properties {
+            history.insert(pointer.id, (pointer.x, pointer.y));
+        }
+        Ok(())
     }
 
     fn ensure_touching_pointers_match(
@@ -404,13 +739,37 @@ impl InputVerifier {
         }
         true
     }
+
+    fn ensure_hovering_pointers_match(
+        &self,
+        device_id: DeviceId,
+        pointer_properties: &[RustPointerProperties],
+    ) -> bool {
+        let Some(pointers) = self.hovering_pointer_ids_by_device.get(&device_id) else {
+            return false;
+        };
+
+        if pointers.len() != pointer_properties.len() {
+            return false;
+        }
+
+        for pointer_property in pointer_properties.iter() {
+            let pointer_id = pointer_property.id;
+            if !pointers.contains(&pointer_id) {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::input::MotionButton;
-    use crate::input_verifier::InputVerifier;
+    use crate::input_verifier::{InputVerifier, SyntheticEvent};
     use crate::DeviceId;
+    use crate::KeyCode;
+    use crate::KeyFlags;
     use crate::MotionFlags;
     use crate::RustPointerProperties;
     use crate::Source;
@@ -422,7 +781,7 @@ mod tests {
     fn bad_down_event() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ true);
         let pointer_properties =
-            Vec::from([RustPointerProperties { id: 0 }, RustPointerProperties { id: 1 }]);
+            Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }, RustPointerProperties { id: 1, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -439,7 +798,7 @@ mod tests {
     #[test]
     fn single_pointer_stream() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -478,7 +837,7 @@ mod tests {
     #[test]
     fn two_pointer_stream() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -492,7 +851,7 @@ mod tests {
             .is_ok());
         // POINTER 1 DOWN
         let two_pointer_properties =
-            Vec::from([RustPointerProperties { id: 0 }, RustPointerProperties { id: 1 }]);
+            Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }, RustPointerProperties { id: 1, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -519,7 +878,7 @@ mod tests {
             )
             .is_ok());
         // ACTION_UP for pointer id=1
-        let pointer_1_properties = Vec::from([RustPointerProperties { id: 1 }]);
+        let pointer_1_properties = Vec::from([RustPointerProperties { id: 1, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -536,7 +895,7 @@ mod tests {
     #[test]
     fn multi_device_stream() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -597,7 +956,7 @@ mod tests {
     #[test]
     fn action_cancel() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -625,7 +984,7 @@ mod tests {
     #[test]
     fn invalid_action_cancel() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -653,7 +1012,7 @@ mod tests {
     #[test]
     fn invalid_up() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -670,7 +1029,7 @@ mod tests {
     #[test]
     fn correct_hover_sequence() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -723,7 +1082,7 @@ mod tests {
     #[test]
     fn double_hover_enter() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -749,98 +1108,87 @@ mod tests {
             .is_err());
     }
 
-    // Send a MOVE without a preceding DOWN event. This is OK because it's from source
-    // MOUSE_RELATIVE, which is used during pointer capture. The verifier should allow such event.
     #[test]
-    fn relative_mouse_move() {
+    fn multi_pointer_hover_sequence() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
-                DeviceId(2),
-                Source::MouseRelative,
-                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                DeviceId(1),
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_HOVER_ENTER,
                 MotionButton::empty(),
                 &pointer_properties,
                 MotionFlags::empty(),
                 MotionButton::empty(),
             )
             .is_ok());
-    }
-
-    // Send a MOVE event with incorrect number of pointers (one of the pointers is missing).
-    #[test]
-    fn move_with_wrong_number_of_pointers() {
-        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        // HOVER_POINTER_DOWN for a second pointer.
+        let two_pointer_properties =
+            Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }, RustPointerProperties { id: 1, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Touchscreen,
-                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_HOVER_POINTER_DOWN
+                    | (1 << input_bindgen::AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT),
                 MotionButton::empty(),
-                &pointer_properties,
+                &two_pointer_properties,
                 MotionFlags::empty(),
                 MotionButton::empty(),
             )
             .is_ok());
-        // POINTER 1 DOWN
-        let two_pointer_properties =
-            Vec::from([RustPointerProperties { id: 0 }, RustPointerProperties { id: 1 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Touchscreen,
-                input_bindgen::AMOTION_EVENT_ACTION_POINTER_DOWN
-                    | (1 << input_bindgen::AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT),
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_HOVER_MOVE,
                 MotionButton::empty(),
                 &two_pointer_properties,
                 MotionFlags::empty(),
                 MotionButton::empty(),
             )
             .is_ok());
-        // MOVE event with 1 pointer missing (the pointer with id = 1). It should be rejected
+        // HOVER_POINTER_EXIT for pointer 0, leaving pointer 1 still hovering.
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Touchscreen,
-                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_HOVER_POINTER_EXIT
+                    | (0 << input_bindgen::AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT),
                 MotionButton::empty(),
-                &pointer_properties,
+                &two_pointer_properties,
                 MotionFlags::empty(),
                 MotionButton::empty(),
             )
-            .is_err());
-    }
-
-    #[test]
-    fn correct_button_press() {
-        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+            .is_ok());
+        let pointer_1_properties = Vec::from([RustPointerProperties { id: 1, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Mouse,
-                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
-                MotionButton::Primary,
-                &pointer_properties,
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_HOVER_EXIT,
+                MotionButton::empty(),
+                &pointer_1_properties,
                 MotionFlags::empty(),
-                MotionButton::Primary,
+                MotionButton::empty(),
             )
             .is_ok());
     }
 
     #[test]
-    fn button_press_without_action_button() {
+    fn hover_pointer_down_without_hover_enter() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let two_pointer_properties =
+            Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }, RustPointerProperties { id: 1, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Mouse,
-                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_HOVER_POINTER_DOWN
+                    | (1 << input_bindgen::AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT),
                 MotionButton::empty(),
-                &pointer_properties,
+                &two_pointer_properties,
                 MotionFlags::empty(),
                 MotionButton::empty(),
             )
@@ -848,32 +1196,26 @@ mod tests {
     }
 
     #[test]
-    fn button_press_with_multiple_action_buttons() {
+    fn device_source_change_mid_gesture_is_rejected() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Mouse,
-                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
-                MotionButton::Back | MotionButton::Forward,
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
                 &pointer_properties,
                 MotionFlags::empty(),
-                MotionButton::Back | MotionButton::Forward,
+                MotionButton::empty(),
             )
-            .is_err());
-    }
-
-    #[test]
-    fn button_press_without_action_button_in_state() {
-        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+            .is_ok());
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Mouse,
-                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
-                MotionButton::Primary,
+                Source::MouseRelative,
+                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                MotionButton::empty(),
                 &pointer_properties,
                 MotionFlags::empty(),
                 MotionButton::empty(),
@@ -882,71 +1224,327 @@ mod tests {
     }
 
     #[test]
-    fn button_release_with_action_button_in_state() {
+    fn device_source_change_allowed_after_reset() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Mouse,
-                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
-                MotionButton::Primary,
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
                 &pointer_properties,
                 MotionFlags::empty(),
-                MotionButton::Primary,
+                MotionButton::empty(),
             )
             .is_ok());
         assert!(verifier
             .process_movement(
                 DeviceId(1),
-                Source::Mouse,
-                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_RELEASE,
-                MotionButton::Primary,
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_UP,
+                MotionButton::empty(),
                 &pointer_properties,
                 MotionFlags::empty(),
-                MotionButton::Primary,
+                MotionButton::empty(),
             )
-            .is_err());
-    }
-
-    #[test]
-    fn nonbutton_action_with_action_button() {
-        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+            .is_ok());
+        verifier.reset_device(DeviceId(1));
         assert!(verifier
             .process_movement(
                 DeviceId(1),
                 Source::Mouse,
-                input_bindgen::AMOTION_EVENT_ACTION_HOVER_ENTER,
-                MotionButton::Primary,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
                 &pointer_properties,
                 MotionFlags::empty(),
                 MotionButton::empty(),
             )
-            .is_err());
+            .is_ok());
     }
 
+    // Send a MOVE without a preceding DOWN event. This is OK because it's from source
+    // MOUSE_RELATIVE, which is used during pointer capture. The verifier should allow such event.
     #[test]
-    fn nonbutton_action_with_action_button_and_state() {
+    fn relative_mouse_move() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
-                DeviceId(1),
-                Source::Mouse,
-                input_bindgen::AMOTION_EVENT_ACTION_HOVER_ENTER,
+                DeviceId(2),
+                Source::MouseRelative,
+                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+    }
+
+    // Send a MOVE event with incorrect number of pointers (one of the pointers is missing).
+    #[test]
+    fn move_with_wrong_number_of_pointers() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+        // POINTER 1 DOWN
+        let two_pointer_properties =
+            Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }, RustPointerProperties { id: 1, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_POINTER_DOWN
+                    | (1 << input_bindgen::AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT),
+                MotionButton::empty(),
+                &two_pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+        // MOVE event with 1 pointer missing (the pointer with id = 1). It should be rejected
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn correct_button_press() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
                 MotionButton::Primary,
                 &pointer_properties,
                 MotionFlags::empty(),
                 MotionButton::Primary,
             )
+            .is_ok());
+    }
+
+    #[test]
+    fn button_press_without_action_button() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn button_press_with_multiple_action_buttons() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::Back | MotionButton::Forward,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::Back | MotionButton::Forward,
+            )
             .is_err());
     }
 
+    #[test]
+    fn button_press_without_action_button_in_state() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::Primary,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn button_release_with_action_button_in_state() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::Primary,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::Primary,
+            )
+            .is_ok());
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_RELEASE,
+                MotionButton::Primary,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::Primary,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn nonbutton_action_with_action_button() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_HOVER_ENTER,
+                MotionButton::Primary,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn nonbutton_action_with_action_button_and_state() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_HOVER_ENTER,
+                MotionButton::Primary,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::Primary,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn stylus_button_rejected_on_mouse_source() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::StylusPrimary,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::StylusPrimary,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn stylus_tip_primary_button_allowed_on_stylus_source() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::Primary,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::Primary,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn mouse_button_rejected_on_stylus_source() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::Back,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::Back,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn reset_device_clears_stale_button_source() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Stylus,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::StylusPrimary,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::StylusPrimary,
+            )
+            .is_ok());
+
+        verifier.reset_device(DeviceId(1));
+
+        // The device has been reconfigured as a mouse; a mouse-only button should no longer be
+        // rejected against the stale stylus source recorded before the reset.
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::Back,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::Back,
+            )
+            .is_ok());
+    }
+
     #[test]
     fn nonbutton_action_with_button_state_change() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -974,7 +1572,7 @@ mod tests {
     #[test]
     fn nonbutton_action_missing_button_state() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1013,7 +1611,7 @@ mod tests {
     #[test]
     fn up_without_button_release() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1053,7 +1651,7 @@ mod tests {
     #[test]
     fn button_press_for_already_pressed_button() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1081,7 +1679,7 @@ mod tests {
     #[test]
     fn button_release_for_unpressed_button() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1098,7 +1696,7 @@ mod tests {
     #[test]
     fn correct_multiple_button_presses_without_down() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1126,7 +1724,7 @@ mod tests {
     #[test]
     fn correct_down_with_button_press() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1178,7 +1776,7 @@ mod tests {
     #[test]
     fn down_with_button_state_change_not_followed_by_button_press() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1207,7 +1805,7 @@ mod tests {
     #[test]
     fn down_with_button_state_change_not_followed_by_enough_button_presses() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1248,7 +1846,7 @@ mod tests {
     #[test]
     fn down_missing_already_pressed_button() {
         let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
-        let pointer_properties = Vec::from([RustPointerProperties { id: 0 }]);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
         assert!(verifier
             .process_movement(
                 DeviceId(1),
@@ -1272,4 +1870,407 @@ mod tests {
             )
             .is_err());
     }
+
+    #[test]
+    fn nan_coordinates_rejected() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: f32::NAN, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn coordinates_outside_surface_extent_rejected() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        verifier.set_surface_extent(DeviceId(1), 100.0, 100.0);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 150.0, y: 50.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn coordinates_without_configured_extent_are_unchecked() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 1e9, y: 1e9 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn pointer_jump_exceeding_limit_rejected() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        verifier.set_pointer_jump_limit(10.0);
+        let down = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &down,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+        let far_move = Vec::from([RustPointerProperties { id: 0, x: 1000.0, y: 1000.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                MotionButton::empty(),
+                &far_move,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn mouse_relative_coordinates_exempt_from_surface_extent_and_jump_limit() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        verifier.set_surface_extent(DeviceId(1), 100.0, 100.0);
+        verifier.set_pointer_jump_limit(10.0);
+        let down = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::MouseRelative,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &down,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+        // A relative-mouse delta well past the surface extent and jump limit is still accepted,
+        // since it isn't an absolute position.
+        let far_move = Vec::from([RustPointerProperties { id: 0, x: 1000.0, y: -1000.0 }]);
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::MouseRelative,
+                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                MotionButton::empty(),
+                &far_move,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn key_down_up() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_DOWN,
+                KeyCode(1),
+                0,
+                KeyFlags::empty(),
+                0,
+            )
+            .is_ok());
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_UP,
+                KeyCode(1),
+                0,
+                KeyFlags::empty(),
+                0,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn key_up_without_down_is_rejected() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_UP,
+                KeyCode(1),
+                0,
+                KeyFlags::empty(),
+                0,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn key_repeat_with_increasing_repeat_count() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_DOWN,
+                KeyCode(1),
+                0,
+                KeyFlags::empty(),
+                0,
+            )
+            .is_ok());
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_DOWN,
+                KeyCode(1),
+                0,
+                KeyFlags::LONG_PRESS,
+                1,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn key_repeat_with_non_increasing_repeat_count_is_rejected() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_DOWN,
+                KeyCode(1),
+                0,
+                KeyFlags::empty(),
+                0,
+            )
+            .is_ok());
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_DOWN,
+                KeyCode(1),
+                0,
+                KeyFlags::empty(),
+                0,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn key_still_down_after_reset_can_be_pressed_again() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_DOWN,
+                KeyCode(1),
+                0,
+                KeyFlags::empty(),
+                0,
+            )
+            .is_ok());
+        verifier.reset_device(DeviceId(1));
+        assert!(verifier
+            .process_key(
+                DeviceId(1),
+                Source::Keyboard,
+                input_bindgen::AKEY_EVENT_ACTION_DOWN,
+                KeyCode(1),
+                0,
+                KeyFlags::empty(),
+                0,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn resynchronize_does_nothing_when_recovery_mode_is_disabled() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .unwrap();
+        assert_eq!(
+            verifier.resynchronize(
+                DeviceId(1),
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                &pointer_properties,
+                MotionButton::empty(),
+            ),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn resynchronize_cancels_a_stuck_gesture_before_a_new_down() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        verifier.set_recovery_mode(true);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .unwrap();
+
+        let new_pointer_properties = Vec::from([RustPointerProperties { id: 1, x: 5.0, y: 5.0 }]);
+        assert_eq!(
+            verifier.resynchronize(
+                DeviceId(1),
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                &new_pointer_properties,
+                MotionButton::empty(),
+            ),
+            Vec::from([SyntheticEvent::Cancel])
+        );
+        // The verifier no longer considers the device to be mid-gesture, so the new DOWN succeeds.
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &new_pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn resynchronize_inserts_pointer_down_for_an_unannounced_pointer_in_a_move() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        verifier.set_recovery_mode(true);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .unwrap();
+
+        // A second pointer shows up directly in a MOVE, skipping its POINTER_DOWN.
+        let move_pointer_properties = Vec::from([
+            RustPointerProperties { id: 0, x: 1.0, y: 1.0 },
+            RustPointerProperties { id: 1, x: 5.0, y: 5.0 },
+        ]);
+        assert_eq!(
+            verifier.resynchronize(
+                DeviceId(1),
+                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                &move_pointer_properties,
+                MotionButton::empty(),
+            ),
+            Vec::from([SyntheticEvent::PointerDown { action_index: 1 }])
+        );
+        // The verifier now considers both pointers to be touching, so the MOVE succeeds.
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Touchscreen,
+                input_bindgen::AMOTION_EVENT_ACTION_MOVE,
+                MotionButton::empty(),
+                &move_pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn resynchronize_releases_a_stale_button_before_up() {
+        let mut verifier = InputVerifier::new("Test", /*should_log*/ false);
+        verifier.set_recovery_mode(true);
+        let pointer_properties = Vec::from([RustPointerProperties { id: 0, x: 0.0, y: 0.0 }]);
+        verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_DOWN,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .unwrap();
+        verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_BUTTON_PRESS,
+                MotionButton::Back,
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::Back,
+            )
+            .unwrap();
+
+        // The UP arrives with an empty button state, but the verifier still thinks BACK is down.
+        assert_eq!(
+            verifier.resynchronize(
+                DeviceId(1),
+                input_bindgen::AMOTION_EVENT_ACTION_UP,
+                &pointer_properties,
+                MotionButton::empty(),
+            ),
+            Vec::from([SyntheticEvent::ButtonRelease { action_button: MotionButton::Back }])
+        );
+        assert!(verifier
+            .process_movement(
+                DeviceId(1),
+                Source::Mouse,
+                input_bindgen::AMOTION_EVENT_ACTION_UP,
+                MotionButton::empty(),
+                &pointer_properties,
+                MotionFlags::empty(),
+                MotionButton::empty(),
+            )
+            .is_ok());
+    }
 }
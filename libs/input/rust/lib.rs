@@ -0,0 +1,32 @@
+/*
+ * Copyright 2023 The Android Open Source Project
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Rust support code for `libinput`, callable from C++ via the `ffi` module.
+
+pub mod ffi;
+pub mod input;
+#[cfg(any(test, feature = "input_synthesizer"))]
+pub mod input_synthesizer;
+pub mod input_verifier;
+
+pub use ffi::RustPointerProperties;
+pub use input::{
+    DeviceId, KeyAction, KeyCode, KeyFlags, MotionAction, MotionButton, MotionFlags, Source,
+    SourceClass,
+};
+#[cfg(any(test, feature = "input_synthesizer"))]
+pub use input_synthesizer::InputSynthesizer;
+pub use input_verifier::{InputVerifier, SyntheticEvent};